@@ -0,0 +1,141 @@
+//! Generation of the contract ABI. See `l1x_sdk::abi`.
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::Signature;
+
+use crate::{has_output, result_ok_type, ContractArg, MethodAttrs, Serializer};
+
+/// Returns the type as it's written in the source, without the extra spaces added by `quote`.
+fn type_name(ty: &syn::Type) -> String {
+    let no_space_after = |c: char| matches!(c, '<' | '&' | '(' | '[' | ':');
+    let no_space_before = |c: char| matches!(c, '<' | '>' | ',' | ';' | ')' | ']' | ':');
+    let name = ty.to_token_stream().to_string();
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ' '
+            && (result.chars().last().map_or(false, no_space_after)
+                || chars.peek().map_or(false, |next| no_space_before(*next)))
+        {
+            continue;
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Returns the name of the argument as it's seen by the caller.
+fn arg_name(pat: &syn::Pat) -> String {
+    match pat {
+        syn::Pat::Ident(pat) => pat.ident.to_string(),
+        pat => pat.to_token_stream().to_string(),
+    }
+}
+
+fn abi_serializer(serializer: Serializer) -> TokenStream2 {
+    match serializer {
+        Serializer::Json => quote! { l1x_sdk::abi::AbiSerializer::Json },
+        Serializer::Borsh => quote! { l1x_sdk::abi::AbiSerializer::Borsh },
+    }
+}
+
+fn abi_type(ty: &syn::Type, serializer: Serializer) -> TokenStream2 {
+    let name = type_name(ty);
+    match serializer {
+        Serializer::Json => quote! { l1x_sdk::abi::AbiType::json::<#ty>(#name) },
+        Serializer::Borsh => quote! { l1x_sdk::abi::AbiType::borsh(#name) },
+    }
+}
+
+/// Generates the expression that creates `l1x_sdk::abi::AbiMethod` describing the method.
+pub(crate) fn abi_method(
+    sig: &Signature,
+    attrs: &MethodAttrs,
+    args: &[ContractArg],
+    args_serializer: Serializer,
+    result_serializer: Serializer,
+) -> TokenStream2 {
+    let name = sig.ident.to_string();
+    let kind = if attrs.init {
+        quote! { l1x_sdk::abi::AbiMethodKind::Init }
+    } else if attrs.is_read_only(sig) {
+        quote! { l1x_sdk::abi::AbiMethodKind::View }
+    } else {
+        quote! { l1x_sdk::abi::AbiMethodKind::Call }
+    };
+    let abi_args = args.iter().map(|arg| {
+        let name = arg_name(&arg.pat);
+        let ty = abi_type(&arg.ty, args_serializer);
+        let deposit = arg.deposit;
+        quote! {
+            l1x_sdk::abi::AbiArg {
+                name: #name.to_string(),
+                ty: #ty,
+                deposit: #deposit,
+            }
+        }
+    });
+    let result_ty = match &sig.output {
+        _ if !has_output(sig, attrs) => None,
+        _ if attrs.handle_result => result_ok_type(sig),
+        syn::ReturnType::Type(_, ty) => Some(ty.as_ref()),
+        syn::ReturnType::Default => None,
+    };
+    let result = match result_ty {
+        Some(ty) => {
+            let ty = abi_type(ty, result_serializer);
+            quote! { Some(#ty) }
+        }
+        None => quote! { None },
+    };
+    let payable = attrs.payable;
+    let only_owner = attrs.only_owner;
+    let private = attrs.private;
+    let args_serializer = abi_serializer(args_serializer);
+    let result_serializer = abi_serializer(result_serializer);
+    quote! {
+        l1x_sdk::abi::AbiMethod {
+            name: #name.to_string(),
+            kind: #kind,
+            payable: #payable,
+            only_owner: #only_owner,
+            private: #private,
+            args_serializer: #args_serializer,
+            args: vec![#(#abi_args),*],
+            result_serializer: #result_serializer,
+            result: #result,
+        }
+    }
+}
+
+/// Generates `__l1x_abi_<method>` function that writes the JSON serialized `method` to the output.
+pub(crate) fn abi_method_export(ident: &syn::Ident, method: &TokenStream2) -> TokenStream2 {
+    let abi_ident = format_ident!("__l1x_abi_{}", ident);
+    quote! {
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        pub extern "C" fn #abi_ident() {
+            let method = #method;
+            let method =
+                serde_json::to_vec(&method).expect("Failed to serialize the ABI using JSON.");
+            l1x_sdk::output(&method);
+        }
+    }
+}
+
+/// Generates `__l1x_abi` function that writes the JSON serialized `l1x_sdk::abi::AbiContract`
+/// with all `methods` to the output.
+pub(crate) fn abi_contract_export(methods: &[TokenStream2]) -> TokenStream2 {
+    quote! {
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        pub extern "C" fn __l1x_abi() {
+            let contract = l1x_sdk::abi::AbiContract {
+                methods: vec![#(#methods),*],
+            };
+            let contract =
+                serde_json::to_vec(&contract).expect("Failed to serialize the ABI using JSON.");
+            l1x_sdk::output(&contract);
+        }
+    }
+}
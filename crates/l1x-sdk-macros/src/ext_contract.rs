@@ -0,0 +1,186 @@
+//! Generation of typed clients for external contracts. See [`crate::ext_contract`].
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{ItemTrait, TraitItem, TraitItemMethod};
+
+use crate::{
+    extract_args, has_output, result_ok_type, ContractArg, ContractAttrs, MethodAttrs, Serializer,
+};
+
+/// Attributes of `#[contract]` methods that only change the contract side of the call.
+const CONTRACT_ONLY_ATTRS: [&str; 3] = ["payable", "only_owner", "private"];
+
+/// Generates the code that serializes the arguments the same way as `#[contract]` deserializes
+/// them.
+fn args_ser(args: &[ContractArg], serializer: Serializer) -> Result<TokenStream2, syn::Error> {
+    if args.is_empty() {
+        return Ok(quote! { Vec::new() });
+    }
+    let pats: Vec<_> = args.iter().map(|arg| &arg.pat).collect();
+    match serializer {
+        Serializer::Json => {
+            let mut fields = TokenStream2::new();
+            for arg in args {
+                let pat = &arg.pat;
+                let ty = &arg.ty;
+                let serde_attrs = &arg.serde_attrs;
+                fields.extend(quote! {
+                    #(#serde_attrs)*
+                    #pat: &'a #ty,
+                });
+            }
+            let serialization = serializer.serialize(quote! { &input }, "the arguments");
+            Ok(quote! {{
+                #[derive(serde::Serialize)]
+                struct Input<'a> {
+                    #fields
+                }
+                let input = Input { #(#pats: &#pats),* };
+                #serialization
+            }})
+        }
+        Serializer::Borsh => {
+            if let Some(attr) = args.iter().flat_map(|arg| &arg.serde_attrs).next() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "#[serde] attributes can't be used with Borsh arguments.",
+                ));
+            }
+            // Borsh serialized struct is a concatenation of its serialized fields
+            Ok(quote! {{
+                let mut args = Vec::new();
+                #(
+                    borsh::BorshSerialize::serialize(&#pats, &mut args)
+                        .expect("Failed to serialize the arguments using Borsh.");
+                )*
+                args
+            }})
+        }
+    }
+}
+
+/// Generates the client method that calls the external contract method.
+fn client_method(
+    method: &mut TraitItemMethod,
+    contract_attrs: &ContractAttrs,
+) -> Result<TokenStream2, syn::Error> {
+    if let Some(attr) = method.attrs.iter().find(|attr| {
+        CONTRACT_ONLY_ATTRS
+            .iter()
+            .any(|name| attr.path.is_ident(name))
+    }) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "The attribute has no effect on the client of the contract.",
+        ));
+    }
+    let attrs = MethodAttrs::extract(&mut method.attrs)?;
+    let args = extract_args(&mut method.sig)?;
+    if let Some(arg) = args.iter().find(|arg| arg.deposit) {
+        return Err(syn::Error::new_spanned(
+            &arg.pat,
+            "#[deposit] argument can't be used in #[ext_contract], calls don't transfer tokens.",
+        ));
+    }
+    if attrs.handle_result && result_ok_type(&method.sig).is_none() {
+        return Err(syn::Error::new_spanned(
+            &method.sig.output,
+            "#[handle_result] method must return `Result`.",
+        ));
+    }
+    let args_serializer = attrs.args.unwrap_or(contract_attrs.serializer);
+    let result_serializer = attrs.result_serializer.unwrap_or(contract_attrs.serializer);
+
+    let ident = &method.sig.ident;
+    let method_name = ident.to_string();
+    let docs = method.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+    let params = args
+        .iter()
+        .map(|ContractArg { pat, ty, .. }| quote! { #pat: #ty });
+    let read_only = attrs.is_read_only(&method.sig);
+    let args = args_ser(&args, args_serializer)?;
+    // The contract outputs only the `Ok` value of `#[handle_result]` methods, and nothing for
+    // `#[init]` methods
+    let output_ty = match &method.sig.output {
+        _ if !has_output(&method.sig, &attrs) => None,
+        _ if attrs.handle_result => result_ok_type(&method.sig),
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ty) => Some(ty.as_ref()),
+    };
+    let (output, result) = match output_ty {
+        None => (quote! { () }, quote! { .map(|_| ()) }),
+        Some(ty) => {
+            let deserialization =
+                result_serializer.deserialize(quote! { &output }, "the return value");
+            (
+                quote! { #ty },
+                quote! {
+                    .map(|output| {
+                        let result: #ty = #deserialization;
+                        result
+                    })
+                },
+            )
+        }
+    };
+
+    Ok(quote! {
+        #(#docs)*
+        pub fn #ident(&self, #(#params),*) -> Option<#output> {
+            let call = l1x_sdk::contract_interaction::ContractCall {
+                contract_address: self.contract_address,
+                method_name: #method_name.to_string(),
+                args: #args,
+                read_only: #read_only,
+                fee_limit: self.fee_limit,
+            };
+            l1x_sdk::call_contract(&call)#result
+        }
+    })
+}
+
+pub(crate) fn ext_contract(
+    contract_attrs: ContractAttrs,
+    mut input: ItemTrait,
+) -> Result<TokenStream2, syn::Error> {
+    let vis = &input.vis;
+    let client_ident = format_ident!("{}Client", input.ident);
+    let doc = format!(
+        "Client of the external contract described by `{}` trait.",
+        input.ident
+    );
+    let mut methods = TokenStream2::new();
+    for item in &mut input.items {
+        match item {
+            TraitItem::Method(method) => methods.extend(client_method(method, &contract_attrs)?),
+            item => {
+                return Err(syn::Error::new_spanned(
+                    item,
+                    "#[ext_contract] only supports methods for now.",
+                ))
+            }
+        }
+    }
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #client_ident {
+            /// The address of the external contract
+            pub contract_address: l1x_sdk::types::Address,
+            /// Fee limit of the calls. Ignored in case of read-only calls.
+            pub fee_limit: u128,
+        }
+
+        impl #client_ident {
+            /// Creates a client of the contract deployed at `contract_address`.
+            pub fn new(contract_address: l1x_sdk::types::Address, fee_limit: u128) -> Self {
+                Self {
+                    contract_address,
+                    fee_limit,
+                }
+            }
+
+            #methods
+        }
+    })
+}
@@ -1,38 +1,569 @@
+#[cfg(feature = "abi")]
+mod abi;
+mod ext_contract;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
+use syn::parse::{Parse, ParseStream};
 use syn::ItemImpl;
+use syn::ItemTrait;
 use syn::Signature;
 use syn::Visibility;
 
-fn input_struct_deser(sig: &Signature) -> TokenStream2 {
-    let mut fields = TokenStream2::new();
-    for arg in &sig.inputs {
+/// Serialization format of method arguments and return values.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Serializer {
+    #[default]
+    Json,
+    Borsh,
+}
+
+impl Parse for Serializer {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        if ident == "json" {
+            Ok(Self::Json)
+        } else if ident == "borsh" {
+            Ok(Self::Borsh)
+        } else {
+            Err(syn::Error::new_spanned(
+                ident,
+                "Expected `json` or `borsh` serializer.",
+            ))
+        }
+    }
+}
+
+impl Serializer {
+    /// Generates the expression that deserializes `bytes` into a value of the inferred type.
+    fn deserialize(&self, bytes: TokenStream2, what: &str) -> TokenStream2 {
+        match self {
+            Self::Json => {
+                let error = format!("Failed to deserialize {} from JSON.", what);
+                quote! { serde_json::from_slice(#bytes).expect(#error) }
+            }
+            Self::Borsh => {
+                let error = format!("Failed to deserialize {} from Borsh.", what);
+                quote! { borsh::BorshDeserialize::try_from_slice(#bytes).expect(#error) }
+            }
+        }
+    }
+
+    /// Generates the expression that serializes `value` into `Vec<u8>`.
+    fn serialize(&self, value: TokenStream2, what: &str) -> TokenStream2 {
+        match self {
+            Self::Json => {
+                let error = format!("Failed to serialize {} using JSON.", what);
+                quote! { serde_json::to_vec(#value).expect(#error) }
+            }
+            Self::Borsh => {
+                let error = format!("Failed to serialize {} using Borsh.", what);
+                quote! { borsh::BorshSerialize::try_to_vec(#value).expect(#error) }
+            }
+        }
+    }
+}
+
+/// Arguments of the `#[contract]` attribute.
+#[derive(Default)]
+struct ContractAttrs {
+    /// `serializer = json|borsh`: the default serializer of arguments and return values.
+    serializer: Serializer,
+}
+
+impl Parse for ContractAttrs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            if key == "serializer" {
+                attrs.serializer = input.parse()?;
+            } else {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "Unknown #[contract] argument.",
+                ));
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(attrs)
+    }
+}
+
+/// A typed argument of a contract method.
+struct ContractArg {
+    pat: Box<syn::Pat>,
+    ty: Box<syn::Type>,
+    /// `#[serde(...)]` attributes of the argument. They are applied to the field of the input
+    /// struct.
+    serde_attrs: Vec<syn::Attribute>,
+    /// `#[deposit]`: the amount of tokens transferred from the caller by `#[payable]` method.
+    deposit: bool,
+}
+
+impl ContractArg {
+    /// Returns `true` if the argument can be omitted in the input: it's an `Option` or it's marked
+    /// with `#[serde(default)]`.
+    fn is_optional(&self) -> bool {
+        let is_option = match self.ty.as_ref() {
+            syn::Type::Path(path) => {
+                path.qself.is_none()
+                    && path
+                        .path
+                        .segments
+                        .last()
+                        .map_or(false, |segment| segment.ident == "Option")
+            }
+            _ => false,
+        };
+        is_option || self.serde_attrs.iter().any(is_serde_default)
+    }
+}
+
+fn is_serde_default(attr: &syn::Attribute) -> bool {
+    match attr.parse_meta() {
+        Ok(syn::Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) => path.is_ident("default"),
+            syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) => {
+                name_value.path.is_ident("default")
+            }
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
+/// Collects typed arguments of the method and removes `#[serde(...)]` and `#[deposit]` attributes
+/// from them.
+fn extract_args(sig: &mut Signature) -> Result<Vec<ContractArg>, syn::Error> {
+    let mut args = Vec::new();
+    for arg in &mut sig.inputs {
         match arg {
-            syn::FnArg::Receiver(_) => todo!(),
+            syn::FnArg::Receiver(_) => (),
             syn::FnArg::Typed(typed) => {
-                let ident = &typed.pat;
-                let ty = &typed.ty;
-                fields.extend(quote! {
-                    #ident: #ty,
+                let (serde_attrs, attrs): (Vec<_>, Vec<_>) = typed
+                    .attrs
+                    .drain(..)
+                    .partition(|attr| attr.path.is_ident("serde"));
+                let (deposit_attrs, attrs): (Vec<_>, Vec<_>) = attrs
+                    .into_iter()
+                    .partition(|attr| attr.path.is_ident("deposit"));
+                typed.attrs = attrs;
+                if let Some(attr) = deposit_attrs.iter().find(|attr| !attr.tokens.is_empty()) {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "The attribute doesn't take arguments.",
+                    ));
+                }
+                args.push(ContractArg {
+                    pat: typed.pat.clone(),
+                    ty: typed.ty.clone(),
+                    serde_attrs,
+                    deposit: !deposit_attrs.is_empty(),
                 });
             }
         }
     }
+    Ok(args)
+}
+
+/// Generates the code that transfers the deposit of `#[payable]` method from the caller.
+///
+/// A `#[payable]` method must have exactly one `#[deposit]` argument, other methods can't have
+/// it.
+fn deposit_transfer(
+    sig: &Signature,
+    attrs: &MethodAttrs,
+    args: &[ContractArg],
+) -> Result<TokenStream2, syn::Error> {
+    let mut deposits = args.iter().filter(|arg| arg.deposit);
+    let deposit = deposits.next();
+    if let Some(arg) = deposits.next() {
+        return Err(syn::Error::new_spanned(
+            &arg.pat,
+            "#[payable] method can have only one #[deposit] argument.",
+        ));
+    }
+    match deposit {
+        None if attrs.payable => Err(syn::Error::new_spanned(
+            sig,
+            "#[payable] method must have a #[deposit] argument.",
+        )),
+        None => Ok(quote! {}),
+        Some(arg) if !attrs.payable => Err(syn::Error::new_spanned(
+            &arg.pat,
+            "#[deposit] argument can only be used in #[payable] method.",
+        )),
+        Some(arg) if attrs.is_read_only(sig) => Err(syn::Error::new_spanned(
+            &arg.pat,
+            "Read-only method can't be #[payable].",
+        )),
+        Some(arg) => {
+            let pat = &arg.pat;
+            Ok(quote! {
+                l1x_sdk::transfer_from_caller(l1x_sdk::types::Balance::from(#pat));
+            })
+        }
+    }
+}
+
+fn arg_list(args: &[ContractArg]) -> TokenStream2 {
+    let mut arg_list = TokenStream2::new();
+    for arg in args {
+        let ident = &arg.pat;
+        arg_list.extend(quote! {
+            #ident,
+        });
+    }
+    arg_list
+}
+
+fn input_struct_deser(args: &[ContractArg], serializer: Serializer) -> TokenStream2 {
+    let mut fields = TokenStream2::new();
+    for arg in args {
+        let ident = &arg.pat;
+        let ty = &arg.ty;
+        let serde_attrs = &arg.serde_attrs;
+        fields.extend(quote! {
+            #(#serde_attrs)*
+            #ident: #ty,
+        });
+    }
+    let derive = match serializer {
+        Serializer::Json => quote! { serde::Deserialize },
+        Serializer::Borsh => quote! { borsh::BorshDeserialize },
+    };
     quote! {
-        #[derive(serde::Deserialize)]
+        #[derive(#derive)]
         struct Input {
             #fields
         }
     }
 }
 
+/// Generates the code that reads the input and deserializes the method arguments.
+///
+/// Methods without arguments don't read the input. If all arguments are optional and the input
+/// is JSON, a missing or empty input is deserialized as `{}`.
+fn input_deser(args: &[ContractArg], serializer: Serializer) -> Result<TokenStream2, syn::Error> {
+    if args.is_empty() {
+        return Ok(quote! {});
+    }
+    if serializer == Serializer::Borsh {
+        if let Some(attr) = args.iter().flat_map(|arg| &arg.serde_attrs).next() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[serde] attributes can't be used with Borsh arguments.",
+            ));
+        }
+    }
+    let arg_struct = input_struct_deser(args, serializer);
+    let arg_list = arg_list(args);
+    let input = if serializer == Serializer::Json && args.iter().all(ContractArg::is_optional) {
+        quote! {
+            l1x_sdk::input()
+                .filter(|input| !input.is_empty())
+                .unwrap_or_else(|| b"{}".to_vec())
+        }
+    } else {
+        quote! {
+            l1x_sdk::input().expect("Expected input since method has arguments.")
+        }
+    };
+    let deserialization = serializer.deserialize(quote! { &#input }, "input");
+    Ok(quote! {
+        #arg_struct
+        let Input {
+            #arg_list
+        } = #deserialization;
+    })
+}
+
+/// Attributes of a contract method that are handled by `#[contract]`.
+#[derive(Default)]
+struct MethodAttrs {
+    /// `#[init]`: the method creates the initial contract state.
+    init: bool,
+    /// `#[view]`: the method doesn't modify the state.
+    view: bool,
+    /// `#[args(json|borsh)]`: the serializer of the arguments.
+    args: Option<Serializer>,
+    /// `#[result_serializer(json|borsh)]`: the serializer of the return value.
+    result_serializer: Option<Serializer>,
+    /// `#[handle_result]`: the method returns `Result` and `Err` aborts the execution.
+    handle_result: bool,
+    /// `#[payable]`: the method transfers the `#[deposit]` argument from the caller.
+    payable: bool,
+    /// `#[only_owner]`: the method can only be called by the contract owner.
+    only_owner: bool,
+    /// `#[private]`: the method can only be called by the contract itself.
+    private: bool,
+}
+
+impl MethodAttrs {
+    /// Parses the attributes handled by `#[contract]` and removes them from `attrs`.
+    fn extract(attrs: &mut Vec<syn::Attribute>) -> Result<Self, syn::Error> {
+        let mut method_attrs = Self::default();
+        let mut result = Ok(());
+        attrs.retain(|attr| match method_attrs.apply(attr) {
+            Ok(handled) => !handled,
+            Err(err) => {
+                result = Err(err);
+                false
+            }
+        });
+        result.map(|_| method_attrs)
+    }
+
+    /// Applies `attr` if it's handled by `#[contract]`. Returns `false` for other attributes.
+    fn apply(&mut self, attr: &syn::Attribute) -> Result<bool, syn::Error> {
+        let flag = |attr: &syn::Attribute| {
+            if attr.tokens.is_empty() {
+                Ok(true)
+            } else {
+                Err(syn::Error::new_spanned(
+                    attr,
+                    "The attribute doesn't take arguments.",
+                ))
+            }
+        };
+        let init_view = |attr: &syn::Attribute| {
+            syn::Error::new_spanned(attr, "#[init] method can't be marked as #[view].")
+        };
+        if attr.path.is_ident("init") {
+            if self.view {
+                return Err(init_view(attr));
+            }
+            self.init = flag(attr)?;
+        } else if attr.path.is_ident("view") {
+            if self.init {
+                return Err(init_view(attr));
+            }
+            self.view = flag(attr)?;
+        } else if attr.path.is_ident("handle_result") {
+            self.handle_result = flag(attr)?;
+        } else if attr.path.is_ident("payable") {
+            self.payable = flag(attr)?;
+        } else if attr.path.is_ident("only_owner") {
+            self.only_owner = flag(attr)?;
+        } else if attr.path.is_ident("private") {
+            self.private = flag(attr)?;
+        } else if attr.path.is_ident("args") {
+            self.args = Some(attr.parse_args()?);
+        } else if attr.path.is_ident("result_serializer") {
+            self.result_serializer = Some(attr.parse_args()?);
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Generates the caller checks of `#[only_owner]` and `#[private]` methods.
+    fn access_checks(&self) -> TokenStream2 {
+        let mut checks = TokenStream2::new();
+        if self.only_owner {
+            checks.extend(quote! { l1x_sdk::assert_owner(); });
+        }
+        if self.private {
+            checks.extend(quote! { l1x_sdk::assert_self(); });
+        }
+        checks
+    }
+
+    /// Returns `true` if the method can't modify the state.
+    ///
+    /// Methods that take `&self` and methods marked with `#[view]` are read-only.
+    fn is_read_only(&self, sig: &Signature) -> bool {
+        match sig.receiver() {
+            Some(syn::FnArg::Receiver(receiver)) => receiver.mutability.is_none(),
+            _ => self.view,
+        }
+    }
+}
+
+/// Returns the `T` type of the method that returns `Result<T, E>`.
+fn result_ok_type(sig: &Signature) -> Option<&syn::Type> {
+    let syn::ReturnType::Type(_, ty) = &sig.output else {
+        return None;
+    };
+    let syn::Type::Path(path) = ty.as_ref() else {
+        return None;
+    };
+    let segment = path
+        .path
+        .segments
+        .last()
+        .filter(|segment| segment.ident == "Result")?;
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns `true` if the wrapper should write the value returned by the method to the output.
+fn has_output(sig: &Signature, attrs: &MethodAttrs) -> bool {
+    if attrs.init {
+        return false;
+    }
+    if attrs.handle_result {
+        return !matches!(result_ok_type(sig), Some(syn::Type::Tuple(tuple)) if tuple.elems.is_empty());
+    }
+    !matches!(sig.output, syn::ReturnType::Default)
+}
+
+/// Generates the code that loads the contract state and calls the method.
+///
+/// Methods without a receiver are called as associated functions. For `&self` and `&mut self`
+/// methods the contract state is read with `l1x_sdk::state_read`. The state is written back with
+/// `l1x_sdk::state_write` only if the method takes `&mut self`.
+///
+/// `#[init]` methods are called only if the state doesn't exist yet, and the returned value is
+/// written as the initial state.
+///
+/// For `#[handle_result]` methods the `Ok` value is unwrapped before the state is written, and the
+/// `Err` value is passed to `l1x_sdk::panic` with its [`std::fmt::Display`] representation.
+fn method_call(
+    struct_type: &syn::Type,
+    sig: &Signature,
+    attrs: &MethodAttrs,
+    arg_list: &TokenStream2,
+) -> Result<TokenStream2, syn::Error> {
+    let ident = &sig.ident;
+    let handle_result = if attrs.handle_result {
+        if result_ok_type(sig).is_none() {
+            return Err(syn::Error::new_spanned(
+                &sig.output,
+                "#[handle_result] method must return `Result`.",
+            ));
+        }
+        quote! {
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => l1x_sdk::panic(&err.to_string()),
+            };
+        }
+    } else {
+        quote! {}
+    };
+    if attrs.init {
+        if let Some(receiver) = sig.receiver() {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "#[init] method can't have a receiver.",
+            ));
+        }
+        if let syn::ReturnType::Default = sig.output {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "#[init] method must return the initial contract state.",
+            ));
+        }
+        return Ok(quote! {
+            if l1x_sdk::state_exists() {
+                l1x_sdk::panic("The contract has already been initialized");
+            }
+            let result = #struct_type::#ident(#arg_list);
+            #handle_result
+            let contract: #struct_type = result;
+            l1x_sdk::state_write(&contract);
+        });
+    }
+    let state_read = quote! {
+        l1x_sdk::state_read().unwrap_or_else(|| {
+            l1x_sdk::panic("The contract is not initialized. Call the #[init] method first.")
+        })
+    };
+    match sig.receiver() {
+        None => Ok(quote! {
+            let result = #struct_type::#ident(#arg_list);
+            #handle_result
+        }),
+        Some(syn::FnArg::Receiver(receiver)) if receiver.reference.is_some() => {
+            if receiver.mutability.is_some() && attrs.view {
+                Err(syn::Error::new_spanned(
+                    receiver,
+                    "#[view] method can't take `&mut self`.",
+                ))
+            } else if receiver.mutability.is_some() {
+                Ok(quote! {
+                    let mut contract: #struct_type = #state_read;
+                    let result = contract.#ident(#arg_list);
+                    #handle_result
+                    l1x_sdk::state_write(&contract);
+                })
+            } else {
+                Ok(quote! {
+                    let contract: #struct_type = #state_read;
+                    let result = contract.#ident(#arg_list);
+                    #handle_result
+                })
+            }
+        }
+        Some(receiver) => Err(syn::Error::new_spanned(
+            receiver,
+            "#[contract] only supports `&self` and `&mut self` receivers.",
+        )),
+    }
+}
+
 /// Walks over public methods and generates wrappers for each method it finds.
 ///
 /// The generated wrapper reads method arguments [`l1x_sdk::input`], deserializes them, and calls the original method.
 /// When the original method returns, the wrapper serializes the returned value and writes the serialized value with `l1x_sdk::output`
 ///
+/// Methods without arguments don't read the input at all. If every argument is an `Option` or is
+/// marked with `#[serde(default)]`, a missing or empty JSON input is treated as `{}`.
+///
+/// Arguments and return values are encoded as JSON by default. Use `#[contract(serializer = borsh)]`
+/// to switch the whole impl section to Borsh, or `#[args(json|borsh)]` and
+/// `#[result_serializer(json|borsh)]` to choose the encoding of a single method. Borsh arguments
+/// are encoded as a struct with the method arguments as fields in the declaration order.
+///
+/// Methods that take `&self` or `&mut self` operate on the contract state. The state is
+/// deserialized with Borsh from `l1x_sdk::STATE_KEY` before the call. After a `&mut self` method
+/// returns, the state is written back to the storage. The contract struct must implement
+/// `BorshSerialize` and `BorshDeserialize`.
+///
+/// Methods that take `&self` or are marked with `#[view]` are read-only. Their wrappers don't write
+/// the state back and mark the call with `l1x_sdk::set_read_only`, so an attempt to modify the
+/// storage, transfer tokens or emit an event panics. Such methods are safe to call with
+/// `read_only: true` in `l1x_sdk::contract_interaction::ContractCall`. `#[view]` can't be used on
+/// `&mut self` methods.
+///
+/// The initial state is created by a method marked with `#[init]`. Such a method can't have a
+/// receiver and must return the contract state. The wrapper panics if the state already exists.
+/// Methods that operate on the state panic if they are called before the contract is initialized.
+///
+/// Methods that return `Result<T, E>` can be marked with `#[handle_result]`. On `Ok` the wrapper
+/// outputs `T`, on `Err` it aborts the execution with the message from `E`'s
+/// [`std::fmt::Display`] implementation, so the call fails and its changes are reverted. Without the
+/// attribute the whole `Result` is serialized as the return value.
+///
+/// A method marked with `#[payable]` accepts L1X tokens. It must have one argument marked with
+/// `#[deposit]`, which is `l1x_sdk::types::U128` or `l1x_sdk::types::Balance`. Before the method is
+/// called, the wrapper transfers this amount from the caller to the contract with
+/// `l1x_sdk::transfer_from_caller`. `#[deposit]` can't be used in other methods, and read-only
+/// methods can't be `#[payable]`.
+///
+/// Methods marked with `#[only_owner]` can only be called by the contract owner, and methods marked
+/// with `#[private]` can only be called by the contract itself, e.g. callbacks. The caller is
+/// checked with `l1x_sdk::assert_owner` and `l1x_sdk::assert_self` before the arguments are
+/// deserialized.
+///
+/// With the `abi` feature the macro also exports `__l1x_abi_<method>` function for each method.
+/// The function outputs the JSON description of the method: its kind, arguments, return value and
+/// serializers. `__l1x_abi` function outputs the descriptions of all methods of the impl section,
+/// so a contract with the `abi` feature can have only one `#[contract]` impl section. See
+/// `l1x_sdk::abi`.
+///
 /// # Example
 /// ```
 /// use l1x_sdk_macros::contract;
@@ -44,55 +575,161 @@ fn input_struct_deser(sig: &Signature) -> TokenStream2 {
 ///     pub fn say(msg: String) {
 ///         // say "hello"
 ///     }
+///
+///     pub fn greet(name: Option<String>, #[serde(default)] times: u32) {
+///         // can be called without input
+///     }
+/// }
+/// ```
+///
+/// ```
+/// use borsh::{BorshDeserialize, BorshSerialize};
+/// use l1x_sdk_macros::contract;
+///
+/// #[derive(BorshSerialize, BorshDeserialize)]
+/// struct Counter {
+///     value: u64,
+/// }
+///
+/// #[contract]
+/// impl Counter {
+///     #[init]
+///     pub fn new(value: u64) -> Self {
+///         Self { value }
+///     }
+///
+///     pub fn get(&self) -> u64 {
+///         self.value
+///     }
+///
+///     #[view]
+///     pub fn max_value() -> u64 {
+///         u64::MAX
+///     }
+///
+///     pub fn increment(&mut self, by: u64) {
+///         self.value += by;
+///     }
+///
+///     #[args(borsh)]
+///     #[result_serializer(borsh)]
+///     pub fn add(&self, other: u64) -> u64 {
+///         self.value + other
+///     }
+///
+///     #[handle_result]
+///     pub fn decrement(&mut self, by: u64) -> Result<u64, String> {
+///         self.value = self.value.checked_sub(by).ok_or("Counter underflow")?;
+///         Ok(self.value)
+///     }
+///
+///     #[payable]
+///     pub fn buy(&mut self, #[deposit] amount: u128) {
+///         self.value += (amount / 1_000) as u64;
+///     }
+///
+///     #[only_owner]
+///     pub fn reset(&mut self) {
+///         self.value = 0;
+///     }
 /// }
 /// ```
 #[proc_macro_attribute]
-pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    if let Ok(input) = syn::parse::<ItemImpl>(item) {
+pub fn contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let contract_attrs = match syn::parse::<ContractAttrs>(attr) {
+        Ok(attrs) => attrs,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    if let Ok(mut input) = syn::parse::<ItemImpl>(item) {
         let struct_type = &input.self_ty;
         let mut generated_code = TokenStream2::new();
-        for item in &input.items {
+        let mut has_init = false;
+        #[cfg(feature = "abi")]
+        let mut abi_methods = Vec::new();
+        for item in &mut input.items {
             match item {
                 syn::ImplItem::Method(method) => {
+                    let attrs = match MethodAttrs::extract(&mut method.attrs) {
+                        Ok(attrs) => attrs,
+                        Err(err) => return TokenStream::from(err.to_compile_error()),
+                    };
+                    if attrs.init {
+                        if has_init {
+                            return TokenStream::from(
+                                syn::Error::new_spanned(
+                                    &method.sig.ident,
+                                    "#[contract] supports only one #[init] method.",
+                                )
+                                .to_compile_error(),
+                            );
+                        }
+                        has_init = true;
+                    }
+                    let args = match extract_args(&mut method.sig) {
+                        Ok(args) => args,
+                        Err(err) => return TokenStream::from(err.to_compile_error()),
+                    };
                     if !matches!(method.vis, Visibility::Public(_)) {
                         continue;
                     }
                     let ident = &method.sig.ident;
-                    let arg_struct = input_struct_deser(&method.sig);
-                    let mut arg_list = TokenStream2::new();
-                    for arg in &method.sig.inputs {
-                        match arg {
-                            syn::FnArg::Receiver(_) => todo!(),
-                            syn::FnArg::Typed(typed) => {
-                                let ident = &typed.pat;
-                                arg_list.extend(quote! {
-                                    #ident,
-                                });
-                            }
-                        }
-                    }
-                    let ouput_serialization = match method.sig.output {
-                        syn::ReturnType::Default => quote! {},
-                        syn::ReturnType::Type(_, _) => quote! {
-                            let result = serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
+                    let args_serializer = attrs.args.unwrap_or(contract_attrs.serializer);
+                    let result_serializer =
+                        attrs.result_serializer.unwrap_or(contract_attrs.serializer);
+                    let input_deserialization = match input_deser(&args, args_serializer) {
+                        Ok(input_deserialization) => input_deserialization,
+                        Err(err) => return TokenStream::from(err.to_compile_error()),
+                    };
+                    let deposit = match deposit_transfer(&method.sig, &attrs, &args) {
+                        Ok(deposit) => deposit,
+                        Err(err) => return TokenStream::from(err.to_compile_error()),
+                    };
+                    let arg_list = arg_list(&args);
+                    let call = match method_call(struct_type, &method.sig, &attrs, &arg_list) {
+                        Ok(call) => call,
+                        Err(err) => return TokenStream::from(err.to_compile_error()),
+                    };
+                    let access_checks = attrs.access_checks();
+                    let read_only = if attrs.is_read_only(&method.sig) {
+                        quote! { l1x_sdk::set_read_only(true); }
+                    } else {
+                        quote! {}
+                    };
+                    let ouput_serialization = if has_output(&method.sig, &attrs) {
+                        let serialization =
+                            result_serializer.serialize(quote! { &result }, "the return value");
+                        quote! {
+                            let result = #serialization;
                             l1x_sdk::output(&result);
-                        },
+                        }
+                    } else {
+                        quote! {}
                     };
                     generated_code.extend(quote! {
                         #[cfg(target_arch = "wasm32")]
                         #[no_mangle]
                         pub extern "C" fn #ident() {
                             l1x_sdk::setup_panic_hook();
-                            #arg_struct
-                            let Input {
-                                #arg_list
-                            } = serde_json::from_slice(
-                                &l1x_sdk::input().expect("Expected input since method has arguments.")
-                            ).expect("Failed to deserialize input from JSON.");
-                            let result = #struct_type::#ident(#arg_list);
+                            #access_checks
+                            #input_deserialization
+                            #read_only
+                            #deposit
+                            #call
                             #ouput_serialization
                         }
-                    })
+                    });
+                    #[cfg(feature = "abi")]
+                    {
+                        let abi_method = abi::abi_method(
+                            &method.sig,
+                            &attrs,
+                            &args,
+                            args_serializer,
+                            result_serializer,
+                        );
+                        generated_code.extend(abi::abi_method_export(ident, &abi_method));
+                        abi_methods.push(abi_method);
+                    }
                 }
                 _ => {
                     return TokenStream::from(
@@ -105,6 +742,8 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
             }
         }
+        #[cfg(feature = "abi")]
+        generated_code.extend(abi::abi_contract_export(&abi_methods));
 
         TokenStream::from(quote! {
             #input
@@ -120,3 +759,59 @@ pub fn contract(_attr: TokenStream, item: TokenStream) -> TokenStream {
         )
     }
 }
+
+/// Generates a typed client for an external contract described by the trait.
+///
+/// The trait itself is not emitted. Instead, the macro generates `<Trait>Client` struct that holds
+/// the contract address and the fee limit. Each trait method becomes a client method that
+/// serializes the arguments the same way as [`macro@contract`] deserializes them, calls
+/// `l1x_sdk::call_contract` and deserializes the returned value. The client method returns `None`
+/// if the call failed.
+///
+/// Methods that take `&self` or are marked with `#[view]` are called with `read_only: true`. The
+/// encoding is chosen with `#[ext_contract(serializer = borsh)]`, `#[args(json|borsh)]` and
+/// `#[result_serializer(json|borsh)]` like in [`macro@contract`].
+///
+/// The trait methods should mirror the attributes of the contract methods that change the output.
+/// The client of a `#[handle_result]` method that returns `Result<T, E>` returns `Option<T>`,
+/// because the contract outputs only the `Ok` value and fails the call on `Err`. The clients of
+/// `#[init]` methods and `#[handle_result]` methods that return `Result<(), E>` return
+/// `Option<()>`, because these methods don't write the output. `#[payable]`, `#[only_owner]`,
+/// `#[private]` and `#[deposit]` only change the contract side of the call, so they are rejected.
+///
+/// # Example
+/// ```
+/// use l1x_sdk::types::{Address, U128};
+/// use l1x_sdk_macros::ext_contract;
+///
+/// #[ext_contract]
+/// pub trait Token {
+///     fn balance_of(&self, account: Address) -> U128;
+///
+///     fn transfer(&mut self, to: Address, amount: U128);
+/// }
+///
+/// fn balance(token: Address, account: Address) -> Option<U128> {
+///     TokenClient::new(token, 1_000).balance_of(account)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let contract_attrs = match syn::parse::<ContractAttrs>(attr) {
+        Ok(attrs) => attrs,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+    match syn::parse::<ItemTrait>(item) {
+        Ok(input) => match ext_contract::ext_contract(contract_attrs, input) {
+            Ok(generated_code) => TokenStream::from(generated_code),
+            Err(err) => TokenStream::from(err.to_compile_error()),
+        },
+        Err(_) => TokenStream::from(
+            syn::Error::new(
+                Span::call_site(),
+                "#[ext_contract] can only be used on traits.",
+            )
+            .to_compile_error(),
+        ),
+    }
+}
@@ -0,0 +1,13 @@
+//! Expansion tests of the macros.
+//!
+//! The wrappers generated by `#[contract]` are only compiled for `wasm32`, so the `pass` cases
+//! check that the annotated items compile on the host, and the `fail` cases check the errors
+//! reported by the macros. The wrappers themselves are built and run by the tests of
+//! `l1x-sdk-sim`.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,6 @@
+use l1x_sdk_macros::contract;
+
+#[contract]
+struct Counter;
+
+fn main() {}
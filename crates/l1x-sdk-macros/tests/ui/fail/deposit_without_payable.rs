@@ -0,0 +1,12 @@
+use l1x_sdk_macros::contract;
+
+struct Shop;
+
+#[contract]
+impl Shop {
+    pub fn buy(#[deposit] amount: u128) {
+        let _ = amount;
+    }
+}
+
+fn main() {}
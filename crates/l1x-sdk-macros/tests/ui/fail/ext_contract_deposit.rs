@@ -0,0 +1,8 @@
+use l1x_sdk_macros::ext_contract;
+
+#[ext_contract]
+trait Token {
+    fn buy(&mut self, #[deposit] amount: u128);
+}
+
+fn main() {}
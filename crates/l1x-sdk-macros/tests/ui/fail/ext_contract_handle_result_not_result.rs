@@ -0,0 +1,9 @@
+use l1x_sdk_macros::ext_contract;
+
+#[ext_contract]
+trait Counter {
+    #[handle_result]
+    fn get(&self) -> u64;
+}
+
+fn main() {}
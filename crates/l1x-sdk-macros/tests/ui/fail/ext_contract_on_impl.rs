@@ -0,0 +1,8 @@
+use l1x_sdk_macros::ext_contract;
+
+struct Token;
+
+#[ext_contract]
+impl Token {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use l1x_sdk_macros::ext_contract;
+
+#[ext_contract]
+trait Token {
+    #[payable]
+    fn buy(&mut self, #[deposit] amount: u128);
+}
+
+fn main() {}
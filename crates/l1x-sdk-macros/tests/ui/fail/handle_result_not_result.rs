@@ -0,0 +1,13 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract]
+impl Counter {
+    #[handle_result]
+    pub fn get() -> u64 {
+        0
+    }
+}
+
+fn main() {}
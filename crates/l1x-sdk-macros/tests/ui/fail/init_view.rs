@@ -0,0 +1,14 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract]
+impl Counter {
+    #[init]
+    #[view]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk_macros::contract;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Counter {
+    value: u64,
+}
+
+#[contract]
+impl Counter {
+    #[init]
+    pub fn new(&self) -> Self {
+        Self { value: self.value }
+    }
+}
+
+fn main() {}
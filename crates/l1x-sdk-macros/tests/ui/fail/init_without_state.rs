@@ -0,0 +1,11 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract]
+impl Counter {
+    #[init]
+    pub fn new() {}
+}
+
+fn main() {}
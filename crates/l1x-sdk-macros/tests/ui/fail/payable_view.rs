@@ -0,0 +1,17 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk_macros::contract;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Shop {
+    sold: u128,
+}
+
+#[contract]
+impl Shop {
+    #[payable]
+    pub fn buy(&self, #[deposit] amount: u128) -> u128 {
+        self.sold + amount
+    }
+}
+
+fn main() {}
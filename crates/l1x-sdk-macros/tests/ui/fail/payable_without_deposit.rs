@@ -0,0 +1,13 @@
+use l1x_sdk_macros::contract;
+
+struct Shop;
+
+#[contract]
+impl Shop {
+    #[payable]
+    pub fn buy(amount: u128) {
+        let _ = amount;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk_macros::contract;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Counter {
+    value: u64,
+}
+
+#[contract]
+impl Counter {
+    pub fn get(self) -> u64 {
+        self.value
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract(serializer = borsh)]
+impl Counter {
+    pub fn set(#[serde(default)] value: u64) {
+        let _ = value;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use l1x_sdk_macros::contract;
+
+struct Shop;
+
+#[contract]
+impl Shop {
+    #[payable]
+    pub fn buy(#[deposit] amount: u128, #[deposit] tip: u128) {
+        let _ = (amount, tip);
+    }
+}
+
+fn main() {}
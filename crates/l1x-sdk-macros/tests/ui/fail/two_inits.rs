@@ -0,0 +1,18 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract]
+impl Counter {
+    #[init]
+    pub fn new() -> Self {
+        Self
+    }
+
+    #[init]
+    pub fn other() -> Self {
+        Self
+    }
+}
+
+fn main() {}
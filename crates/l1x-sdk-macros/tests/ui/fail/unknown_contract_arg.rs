@@ -0,0 +1,12 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract(encoding = borsh)]
+impl Counter {
+    pub fn get() -> u64 {
+        0
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use l1x_sdk_macros::contract;
+
+struct Counter;
+
+#[contract]
+impl Counter {
+    #[args(cbor)]
+    pub fn set(value: u64) {
+        let _ = value;
+    }
+}
+
+fn main() {}
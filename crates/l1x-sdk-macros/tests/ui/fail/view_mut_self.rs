@@ -0,0 +1,17 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk_macros::contract;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Counter {
+    value: u64,
+}
+
+#[contract]
+impl Counter {
+    #[view]
+    pub fn increment(&mut self) {
+        self.value += 1;
+    }
+}
+
+fn main() {}
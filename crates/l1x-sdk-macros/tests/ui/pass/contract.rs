@@ -0,0 +1,99 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::types::{Address, U128};
+use l1x_sdk_macros::contract;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Counter {
+    value: u64,
+}
+
+#[contract]
+impl Counter {
+    #[init]
+    pub fn new(value: u64) -> Self {
+        Self { value }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+
+    #[view]
+    pub fn max_value() -> u64 {
+        u64::MAX
+    }
+
+    pub fn increment(&mut self, by: u64) {
+        self.value += by;
+    }
+
+    pub fn greet(name: Option<String>, #[serde(default)] times: u32) -> String {
+        format!("{:?} {}", name, times)
+    }
+
+    #[args(borsh)]
+    #[result_serializer(borsh)]
+    pub fn add(&self, other: u64) -> u64 {
+        self.value + other
+    }
+
+    #[handle_result]
+    pub fn decrement(&mut self, by: u64) -> Result<u64, String> {
+        self.value = self.value.checked_sub(by).ok_or("Counter underflow")?;
+        Ok(self.value)
+    }
+
+    #[handle_result]
+    pub fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    #[payable]
+    pub fn buy(&mut self, #[deposit] amount: U128) {
+        self.value += u128::from(amount) as u64;
+    }
+
+    #[only_owner]
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+
+    #[private]
+    pub fn callback(&mut self, caller: Address) {
+        let _ = caller;
+    }
+
+    fn helper(&self) -> u64 {
+        self.value
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Registry {
+    owner: Option<Address>,
+}
+
+#[contract(serializer = borsh)]
+impl Registry {
+    #[init]
+    #[handle_result]
+    pub fn create(owner: Address) -> Result<Self, String> {
+        Ok(Self { owner: Some(owner) })
+    }
+
+    #[args(json)]
+    #[result_serializer(json)]
+    pub fn owner(&self) -> Option<Address> {
+        self.owner
+    }
+}
+
+fn main() {
+    let mut counter = Counter::new(1);
+    counter.increment(2);
+    assert_eq!(counter.get(), 3);
+    assert_eq!(counter.helper(), 3);
+    assert_eq!(counter.decrement(4), Err("Counter underflow".to_string()));
+    assert_eq!(Counter::max_value(), u64::MAX);
+    assert!(Registry::create(Address::from([1; 20])).is_ok());
+}
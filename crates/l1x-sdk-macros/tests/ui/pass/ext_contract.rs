@@ -0,0 +1,54 @@
+use l1x_sdk::types::{Address, U128};
+use l1x_sdk_macros::ext_contract;
+
+#[ext_contract]
+pub trait Token {
+    /// Returns the balance of `account`
+    fn balance_of(&self, account: Address) -> U128;
+
+    fn transfer(&mut self, to: Address, amount: U128);
+
+    #[view]
+    fn total_supply() -> U128;
+
+    fn memo(&self, text: Option<String>, #[serde(default)] times: u32) -> String;
+}
+
+#[ext_contract(serializer = borsh)]
+trait Counter {
+    fn get(&self) -> u64;
+
+    #[args(json)]
+    #[result_serializer(json)]
+    fn increment(&mut self, by: u64) -> u64;
+
+    #[init]
+    fn init(value: u64) -> Self;
+
+    #[handle_result]
+    fn decrement(&mut self, by: u64) -> Result<u64, String>;
+
+    #[handle_result]
+    fn reset(&mut self) -> Result<(), String>;
+}
+
+fn balance(token: &TokenClient, account: Address) -> Option<U128> {
+    token.balance_of(account)
+}
+
+fn decrement(counter: &CounterClient) -> Option<u64> {
+    counter.decrement(1)
+}
+
+fn init_and_reset(counter: &CounterClient) -> Option<((), ())> {
+    Some((counter.init(0)?, counter.reset()?))
+}
+
+fn main() {
+    let token = TokenClient::new(Address::from([1; 20]), 1_000);
+    assert_eq!(token.fee_limit, 1_000);
+    let _ = balance;
+    let _ = decrement;
+    let _ = init_and_reset;
+    let _ = CounterClient::new(Address::from([2; 20]), 0).contract_address;
+}
@@ -0,0 +1,62 @@
+use l1x_sdk::types::Address;
+use std::fmt;
+
+/// Errors returned by [`crate::Simulator`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimError {
+    /// The wasm binary can't be compiled
+    InvalidWasm(String),
+    /// A contract is already deployed at the address
+    ContractAlreadyExists(Address),
+    /// No contract is deployed at the address
+    ContractNotFound(Address),
+    /// The contract can't be instantiated, e.g. it imports an unknown function
+    Instantiation(String),
+    /// The contract doesn't export the method
+    MethodNotFound(String),
+    /// The contract called `panic`. `message` is the last message written by the contract.
+    Panic {
+        contract: Address,
+        message: Option<String>,
+    },
+    /// The contract tried to modify the state in read-only context
+    ReadOnly,
+    /// Too many nested `call_contract` calls
+    CallDepthExceeded,
+    /// The fee of the call exceeds its fee limit
+    FeeLimitExceeded,
+    /// The contract called a host function with invalid arguments
+    InvalidHostCall(String),
+    /// The arguments can't be serialized
+    Serialization(String),
+    /// The wasm execution trapped, e.g. on `unreachable` instruction
+    Trap(String),
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidWasm(err) => write!(f, "Invalid wasm binary: {}", err),
+            Self::ContractAlreadyExists(address) => {
+                write!(f, "Contract {} already exists", address)
+            }
+            Self::ContractNotFound(address) => write!(f, "Contract {} not found", address),
+            Self::Instantiation(err) => write!(f, "Contract instantiation failed: {}", err),
+            Self::MethodNotFound(method) => write!(f, "Method {} not found", method),
+            Self::Panic { contract, message } => match message {
+                Some(message) => write!(f, "Contract {} panicked: {}", contract, message),
+                None => write!(f, "Contract {} panicked", contract),
+            },
+            Self::ReadOnly => write!(f, "The state can't be modified in read-only context"),
+            Self::CallDepthExceeded => write!(f, "Maximum call depth exceeded"),
+            Self::FeeLimitExceeded => write!(f, "Fee limit exceeded"),
+            Self::InvalidHostCall(err) => write!(f, "Invalid host call: {}", err),
+            Self::Serialization(err) => write!(f, "Serialization failed: {}", err),
+            Self::Trap(err) => write!(f, "Execution trapped: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
+impl wasmi::core::HostError for SimError {}
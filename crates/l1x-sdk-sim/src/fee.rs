@@ -0,0 +1,100 @@
+use l1x_sdk::types::Address;
+
+/// Fees of the operations metered by the simulator.
+///
+/// Fees are abstract units: the simulator doesn't deduct them from balances, it only reports them
+/// and enforces fee limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostTable {
+    /// Per executed wasm instruction
+    pub instruction: u64,
+    /// Per call of a host function
+    pub host_call: u64,
+    /// Per byte of keys and values read from the storage
+    pub storage_read_byte: u64,
+    /// Per byte of keys and values written to or removed from the storage
+    pub storage_write_byte: u64,
+    /// Per byte written to or read from registers
+    pub register_byte: u64,
+    /// Per `call_contract` call
+    pub call_contract: u64,
+    /// Per `call_contract` call and level of its depth. Deeper calls are more expensive.
+    pub call_depth: u64,
+}
+
+impl Default for CostTable {
+    fn default() -> Self {
+        Self {
+            instruction: 1,
+            host_call: 100,
+            storage_read_byte: 10,
+            storage_write_byte: 50,
+            register_byte: 1,
+            call_contract: 5_000,
+            call_depth: 1_000,
+        }
+    }
+}
+
+/// Fees of a call by the kind of operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub instructions: u64,
+    pub host_calls: u64,
+    pub storage_read: u64,
+    pub storage_write: u64,
+    pub registers: u64,
+    /// The fee of making nested calls, excluding the fees of the called methods
+    pub call_contract: u64,
+}
+
+impl FeeBreakdown {
+    /// Returns the sum of the fees.
+    pub fn total(&self) -> u64 {
+        self.instructions
+            .saturating_add(self.host_calls)
+            .saturating_add(self.storage_read)
+            .saturating_add(self.storage_write)
+            .saturating_add(self.registers)
+            .saturating_add(self.call_contract)
+    }
+}
+
+/// The fee of a call and its nested calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallFee {
+    pub contract: Address,
+    pub method: String,
+    pub fee_limit: u64,
+    /// The fee of the call itself
+    pub breakdown: FeeBreakdown,
+    /// Fees of the nested calls in the order they were made, including failed calls
+    pub nested: Vec<CallFee>,
+}
+
+impl CallFee {
+    pub(crate) fn new(contract: Address, method: String, fee_limit: u64) -> Self {
+        Self {
+            contract,
+            method,
+            fee_limit,
+            breakdown: FeeBreakdown::default(),
+            nested: Vec::new(),
+        }
+    }
+
+    /// Returns the fee paid for the call including the nested calls. A call that exceeds its fee
+    /// limit pays the limit.
+    pub fn total(&self) -> u64 {
+        self.spent().min(self.fee_limit)
+    }
+
+    /// Returns the fee of the operations executed by the call, which can exceed the fee limit.
+    pub(crate) fn spent(&self) -> u64 {
+        self.nested
+            .iter()
+            .fold(self.breakdown.total(), |total, nested| {
+                total.saturating_add(nested.total())
+            })
+    }
+}
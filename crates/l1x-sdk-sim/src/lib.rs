@@ -0,0 +1,791 @@
+//! A local simulator of the L1X blockchain for integration tests of compiled contracts.
+//!
+//! [`Simulator`] loads `wasm32-unknown-unknown` contract binaries, runs them with the [`wasmi`]
+//! interpreter and implements all host functions declared in `l1x-sys`. Several contracts can be
+//! deployed at once, `call_contract` calls are routed between them.
+//!
+//! Calls are metered: wasm instructions and host calls are charged according to the
+//! [`CostTable`], the fee limits of calls are enforced and every [`CallOutcome`] reports the fee
+//! breakdown of the call and its nested calls.
+//!
+//! # Example
+//! ```no_run
+//! use l1x_sdk::types::Address;
+//! use l1x_sdk_sim::Simulator;
+//!
+//! let owner = Address::from([1; 20]);
+//! let mut sim = Simulator::new();
+//! let wasm = std::fs::read("target/wasm32-unknown-unknown/release/counter.wasm").unwrap();
+//! let counter = sim.deploy(owner, &wasm).unwrap();
+//!
+//! sim.call_json(owner, counter, "new", &serde_json::json!({ "value": 1 }))
+//!     .unwrap();
+//! let value: u64 = sim.view_json(owner, counter, "get", &()).unwrap().json().unwrap();
+//! assert_eq!(value, 1);
+//! ```
+mod error;
+mod fee;
+mod runtime;
+mod world;
+
+pub use error::SimError;
+pub use fee::{CallFee, CostTable, FeeBreakdown};
+pub use runtime::{MAX_CALL_DEPTH, RUNTIME_VERSION};
+pub use world::{Event, Log};
+
+use l1x_sdk::types::{Address, Balance, BlockHash, BlockNumber, TimeStamp};
+use runtime::CallContext;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use world::{Contract, World};
+
+/// The result of a successful call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallOutcome {
+    /// The data written by the called method with `output`
+    pub output: Vec<u8>,
+    /// Messages written during the call, including nested calls
+    pub logs: Vec<Log>,
+    /// Events emitted during the call, including nested calls
+    pub events: Vec<Event>,
+    /// The fee of the call
+    pub fee: CallFee,
+}
+
+impl CallOutcome {
+    /// Deserializes JSON output.
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.output)
+    }
+
+    /// Deserializes Borsh output.
+    pub fn borsh<T: borsh::BorshDeserialize>(&self) -> std::io::Result<T> {
+        T::try_from_slice(&self.output)
+    }
+}
+
+/// The simulated blockchain.
+pub struct Simulator {
+    engines: Arc<[wasmi::Engine]>,
+    costs: CostTable,
+    fee_limit: u64,
+    world: World,
+    nonce: u64,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulator {
+    /// Creates a simulator without contracts, with the default [`CostTable`] and unlimited fee
+    /// limit.
+    pub fn new() -> Self {
+        Self {
+            engines: runtime::engines(),
+            costs: CostTable::default(),
+            fee_limit: u64::MAX,
+            world: World::default(),
+            nonce: 0,
+        }
+    }
+
+    /// Deploys the contract owned by `owner` at a new address and returns the address.
+    pub fn deploy(&mut self, owner: Address, wasm: &[u8]) -> Result<Address, SimError> {
+        let address = loop {
+            self.nonce += 1;
+            let mut bytes = [0u8; 20];
+            bytes[..4].copy_from_slice(b"sim_");
+            bytes[12..].copy_from_slice(&self.nonce.to_be_bytes());
+            let address = Address::from(bytes);
+            if !self.world.contracts.contains_key(&address) {
+                break address;
+            }
+        };
+        self.deploy_at(address, owner, wasm)?;
+        Ok(address)
+    }
+
+    /// Deploys the contract owned by `owner` at `address`.
+    pub fn deploy_at(
+        &mut self,
+        address: Address,
+        owner: Address,
+        wasm: &[u8],
+    ) -> Result<(), SimError> {
+        if self.world.contracts.contains_key(&address) {
+            return Err(SimError::ContractAlreadyExists(address));
+        }
+        let mut contract = Contract {
+            wasm: wasm.into(),
+            modules: Vec::new(),
+            owner,
+            storage: BTreeMap::new(),
+        };
+        // Validates the binary
+        contract
+            .module(&self.engines, 0)
+            .map_err(|err| SimError::InvalidWasm(err.to_string()))?;
+        self.world.contracts.insert(address, contract);
+        Ok(())
+    }
+
+    /// Calls `method` of `contract` on behalf of `caller` with the raw `input`.
+    pub fn call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        method: &str,
+        input: Vec<u8>,
+    ) -> Result<CallOutcome, SimError> {
+        self.execute(caller, contract, method, input, false)
+    }
+
+    /// Calls `method` of `contract` with JSON serialized `args`.
+    pub fn call_json<A: Serialize + ?Sized>(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        method: &str,
+        args: &A,
+    ) -> Result<CallOutcome, SimError> {
+        self.call(caller, contract, method, json_input(args)?)
+    }
+
+    /// Calls `method` of `contract` in read-only context.
+    pub fn view(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        method: &str,
+        input: Vec<u8>,
+    ) -> Result<CallOutcome, SimError> {
+        self.execute(caller, contract, method, input, true)
+    }
+
+    /// Calls `method` of `contract` in read-only context with JSON serialized `args`.
+    pub fn view_json<A: Serialize + ?Sized>(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        method: &str,
+        args: &A,
+    ) -> Result<CallOutcome, SimError> {
+        self.view(caller, contract, method, json_input(args)?)
+    }
+
+    fn execute(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        method: &str,
+        input: Vec<u8>,
+        read_only: bool,
+    ) -> Result<CallOutcome, SimError> {
+        let logs_len = self.world.logs.len();
+        let events_len = self.world.events.len();
+        let context = CallContext {
+            contract,
+            caller,
+            method: method.to_string(),
+            input,
+            read_only,
+            depth: 0,
+            fee_limit: self.fee_limit,
+        };
+        let (fee, result) = runtime::execute(&self.engines, self.costs, &mut self.world, context);
+
+        Ok(CallOutcome {
+            output: result?,
+            logs: self.world.logs[logs_len..].to_vec(),
+            events: self.world.events[events_len..].to_vec(),
+            fee,
+        })
+    }
+
+    /// Returns the fees of the metered operations.
+    pub fn costs(&self) -> &CostTable {
+        &self.costs
+    }
+
+    /// Sets the fees of the metered operations.
+    pub fn set_costs(&mut self, costs: CostTable) {
+        self.costs = costs;
+    }
+
+    /// Returns the fee limit of the calls made with [`Simulator::call`] and [`Simulator::view`].
+    pub fn fee_limit(&self) -> u64 {
+        self.fee_limit
+    }
+
+    /// Sets the fee limit of the calls made with [`Simulator::call`] and [`Simulator::view`].
+    /// Unlimited by default.
+    pub fn set_fee_limit(&mut self, fee_limit: u64) {
+        self.fee_limit = fee_limit;
+    }
+
+    /// Returns the value stored by `contract` under `key`.
+    pub fn storage(&self, contract: &Address, key: &[u8]) -> Option<&[u8]> {
+        self.world
+            .contracts
+            .get(contract)?
+            .storage
+            .get(key)
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the whole storage of `contract`.
+    pub fn storage_entries(&self, contract: &Address) -> Option<&BTreeMap<Vec<u8>, Vec<u8>>> {
+        self.world
+            .contracts
+            .get(contract)
+            .map(|contract| &contract.storage)
+    }
+
+    /// Writes key-value into the storage of `contract`.
+    pub fn set_storage(
+        &mut self,
+        contract: &Address,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), SimError> {
+        self.world
+            .contracts
+            .get_mut(contract)
+            .ok_or(SimError::ContractNotFound(*contract))?
+            .storage
+            .insert(key, value);
+        Ok(())
+    }
+
+    /// Returns the balance of `address`.
+    pub fn balance(&self, address: &Address) -> Balance {
+        self.world.balance(address)
+    }
+
+    /// Sets the balance of `address`.
+    pub fn set_balance(&mut self, address: Address, balance: Balance) {
+        self.world.balances.insert(address, balance);
+    }
+
+    /// Returns all messages written by contracts.
+    pub fn logs(&self) -> &[Log] {
+        &self.world.logs
+    }
+
+    /// Returns all events emitted by contracts.
+    pub fn events(&self) -> &[Event] {
+        &self.world.events
+    }
+
+    /// Returns the number of the current block.
+    pub fn block_number(&self) -> BlockNumber {
+        self.world.block.number
+    }
+
+    /// Sets the number of the current block.
+    pub fn set_block_number(&mut self, block_number: BlockNumber) {
+        self.world.block.number = block_number;
+    }
+
+    /// Returns the timestamp of the current block.
+    pub fn block_timestamp(&self) -> TimeStamp {
+        self.world.block.timestamp
+    }
+
+    /// Sets the timestamp of the current block.
+    pub fn set_block_timestamp(&mut self, block_timestamp: TimeStamp) {
+        self.world.block.timestamp = block_timestamp;
+    }
+
+    /// Returns the hash of the current block.
+    pub fn block_hash(&self) -> BlockHash {
+        self.world.block.hash
+    }
+
+    /// Sets the hash of the current block.
+    pub fn set_block_hash(&mut self, block_hash: BlockHash) {
+        self.world.block.hash = block_hash;
+    }
+
+    /// Increases the block number by `blocks`.
+    pub fn advance_blocks(&mut self, blocks: BlockNumber) {
+        self.world.block.number += blocks;
+    }
+
+    /// Increases the block timestamp by `secs`.
+    pub fn advance_time(&mut self, secs: TimeStamp) {
+        self.world.block.timestamp += secs;
+    }
+}
+
+fn json_input<A: Serialize + ?Sized>(args: &A) -> Result<Vec<u8>, SimError> {
+    serde_json::to_vec(args).map_err(|err| SimError::Serialization(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use l1x_sdk::contract_interaction::ContractCall;
+
+    fn alice() -> Address {
+        Address::from([1; 20])
+    }
+
+    fn proxy() -> Address {
+        Address::from([2; 20])
+    }
+
+    const STORE_WAT: &str = r#"
+    (module
+      (import "env" "input" (func $input (param i64)))
+      (import "env" "register_len" (func $register_len (param i64) (result i64)))
+      (import "env" "read_register" (func $read_register (param i64 i64)))
+      (import "env" "storage_write" (func $storage_write (param i64 i64 i64 i64 i64) (result i64)))
+      (import "env" "storage_read" (func $storage_read (param i64 i64 i64) (result i64)))
+      (import "env" "output" (func $output (param i64 i64)))
+      (import "env" "msg" (func $msg (param i64 i64)))
+      (import "env" "panic" (func $panic))
+      (import "env" "emit_event_experimental" (func $emit_event (param i64 i64) (result i64)))
+      (import "env" "caller_address" (func $caller_address (param i64)))
+      (import "env" "transfer_from_caller" (func $transfer_from_caller (param i64 i64) (result i64)))
+      (memory (export "memory") 1)
+      (data (i32.const 0) "key")
+      (data (i32.const 16) "failed")
+      ;; Reads the input to 1024 and returns its length
+      (func $read_input (result i64)
+        (call $input (i64.const 0))
+        (call $read_register (i64.const 0) (i64.const 1024))
+        (call $register_len (i64.const 0)))
+      ;; Stores the input under "key"
+      (func (export "set")
+        (drop (call $storage_write
+          (i64.const 0) (i64.const 3) (i64.const 1024) (call $read_input) (i64.const 1))))
+      ;; Outputs the value stored under "key"
+      (func (export "get")
+        (if (i64.eqz (call $storage_read (i64.const 0) (i64.const 3) (i64.const 0)))
+          (then (return)))
+        (call $read_register (i64.const 0) (i64.const 1024))
+        (call $output (i64.const 1024) (call $register_len (i64.const 0))))
+      (func (export "fail")
+        (call $msg (i64.const 16) (i64.const 6))
+        (call $panic))
+      ;; Stores the input, emits it as the event and panics
+      (func (export "set_and_fail")
+        (drop (call $storage_write
+          (i64.const 0) (i64.const 3) (i64.const 1024) (call $read_input) (i64.const 1)))
+        (drop (call $emit_event (i64.const 1024) (call $register_len (i64.const 0))))
+        (call $panic))
+      ;; Emits the input as the event
+      (func (export "emit")
+        (drop (call $emit_event (i64.const 1024) (call $read_input))))
+      ;; Outputs the caller address
+      (func (export "whoami")
+        (call $caller_address (i64.const 0))
+        (call $read_register (i64.const 0) (i64.const 1024))
+        (call $output (i64.const 1024) (i64.const 20)))
+      ;; Transfers the amount passed as the input from the caller
+      (func (export "deposit")
+        (if (i64.eqz (call $transfer_from_caller (i64.const 1024) (call $read_input)))
+          (then (call $panic))))
+    )
+    "#;
+
+    fn wat_data(offset: u32, call: &ContractCall) -> (String, usize) {
+        let bytes = call.try_to_vec().unwrap();
+        let escaped: String = bytes.iter().map(|byte| format!("\\{:02x}", byte)).collect();
+        (
+            format!("(data (i32.const {}) \"{}\")", offset, escaped),
+            bytes.len(),
+        )
+    }
+
+    /// The contract that forwards calls to `target`.
+    fn proxy_wasm(target: Address) -> Vec<u8> {
+        let limited_call =
+            |contract_address, method_name: &str, args: &[u8], read_only, fee_limit| ContractCall {
+                contract_address,
+                method_name: method_name.to_string(),
+                args: args.to_vec(),
+                read_only,
+                fee_limit,
+            };
+        let call = |contract_address, method_name: &str, args: &[u8], read_only| {
+            limited_call(contract_address, method_name, args, read_only, u128::MAX)
+        };
+        let (get_data, get_len) = wat_data(0, &call(target, "get", b"", true));
+        let (set_data, set_len) = wat_data(256, &call(target, "set", b"proxied", false));
+        let (whoami_data, whoami_len) = wat_data(512, &call(target, "whoami", b"", true));
+        let (recurse_data, recurse_len) = wat_data(768, &call(proxy(), "recurse", b"", true));
+        let (set_and_fail_data, set_and_fail_len) =
+            wat_data(1280, &call(target, "set_and_fail", b"proxied", false));
+        let (set_limited_data, set_limited_len) =
+            wat_data(1536, &limited_call(target, "set", b"proxied", false, 500));
+        let wat = format!(
+            r#"
+            (module
+              (import "env" "call_contract" (func $call_contract (param i64 i64 i64) (result i64)))
+              (import "env" "register_len" (func $register_len (param i64) (result i64)))
+              (import "env" "read_register" (func $read_register (param i64 i64)))
+              (import "env" "output" (func $output (param i64 i64)))
+              (import "env" "msg" (func $msg (param i64 i64)))
+              (memory (export "memory") 1)
+              {get_data}
+              {set_data}
+              {whoami_data}
+              {recurse_data}
+              {set_and_fail_data}
+              {set_limited_data}
+              (data (i32.const 1000) "bottom")
+              ;; Calls the contract and outputs the result
+              (func $forward (param $call i64) (param $len i64)
+                (if (i64.eqz (call $call_contract (local.get $call) (local.get $len) (i64.const 0)))
+                  (then (return)))
+                (call $read_register (i64.const 0) (i64.const 2048))
+                (call $output (i64.const 2048) (call $register_len (i64.const 0))))
+              (func (export "forward_get") (call $forward (i64.const 0) (i64.const {get_len})))
+              (func (export "forward_set") (call $forward (i64.const 256) (i64.const {set_len})))
+              (func (export "forward_whoami")
+                (call $forward (i64.const 512) (i64.const {whoami_len})))
+              (func (export "forward_set_and_fail")
+                (call $forward (i64.const 1280) (i64.const {set_and_fail_len})))
+              (func (export "forward_set_limited")
+                (call $forward (i64.const 1536) (i64.const {set_limited_len})))
+              ;; Makes the nested call and traps
+              (func (export "forward_set_then_trap")
+                (call $forward (i64.const 256) (i64.const {set_len}))
+                (unreachable))
+              (func (export "recurse")
+                (if (i64.eqz (call $call_contract (i64.const 768) (i64.const {recurse_len}) (i64.const 0)))
+                  (then (call $msg (i64.const 1000) (i64.const 6)))))
+            )
+            "#
+        );
+        wat::parse_str(wat).unwrap()
+    }
+
+    fn deploy_store(sim: &mut Simulator) -> Address {
+        sim.deploy(alice(), &wat::parse_str(STORE_WAT).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_storage_and_output() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+
+        assert!(sim
+            .view(alice(), store, "get", vec![])
+            .unwrap()
+            .output
+            .is_empty());
+        sim.call(alice(), store, "set", b"value".to_vec()).unwrap();
+
+        assert_eq!(
+            sim.view(alice(), store, "get", vec![]).unwrap().output,
+            b"value"
+        );
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+        assert_eq!(sim.storage_entries(&store).unwrap().len(), 1);
+
+        sim.set_storage(&store, b"key".to_vec(), b"other".to_vec())
+            .unwrap();
+        assert_eq!(
+            sim.view(alice(), store, "get", vec![]).unwrap().output,
+            b"other"
+        );
+    }
+
+    #[test]
+    fn test_json_input() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+
+        sim.call_json(alice(), store, "set", &serde_json::json!({ "a": 1 }))
+            .unwrap();
+        let outcome = sim.view_json(alice(), store, "get", &()).unwrap();
+        assert_eq!(
+            outcome.json::<serde_json::Value>().unwrap(),
+            serde_json::json!({ "a": 1 })
+        );
+    }
+
+    #[test]
+    fn test_panic() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+
+        assert_eq!(
+            sim.call(alice(), store, "fail", vec![]),
+            Err(SimError::Panic {
+                contract: store,
+                message: Some("failed".to_string())
+            })
+        );
+        assert_eq!(
+            sim.logs(),
+            &[Log {
+                contract: store,
+                message: "failed".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_only() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+
+        assert_eq!(
+            sim.view(alice(), store, "set", b"value".to_vec()),
+            Err(SimError::ReadOnly)
+        );
+        assert_eq!(
+            sim.view(alice(), store, "emit", b"event".to_vec()),
+            Err(SimError::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn test_events_and_transfers() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.set_balance(alice(), 100);
+
+        sim.call(alice(), store, "deposit", 40u128.to_le_bytes().to_vec())
+            .unwrap();
+        assert_eq!(sim.balance(&alice()), 60);
+        assert_eq!(sim.balance(&store), 40);
+        assert!(matches!(
+            sim.call(alice(), store, "deposit", 61u128.to_le_bytes().to_vec()),
+            Err(SimError::Panic { .. })
+        ));
+        assert_eq!(sim.balance(&alice()), 60);
+
+        let event = 7u32.try_to_vec().unwrap();
+        let outcome = sim.call(alice(), store, "emit", event.clone()).unwrap();
+        assert_eq!(
+            outcome.events,
+            vec![Event {
+                contract: store,
+                data: event
+            }]
+        );
+        assert_eq!(sim.events()[0].decode::<u32>().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_call_contract() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.deploy_at(proxy(), alice(), &proxy_wasm(store)).unwrap();
+
+        sim.call(alice(), store, "set", b"value".to_vec()).unwrap();
+        assert_eq!(
+            sim.view(alice(), proxy(), "forward_get", vec![])
+                .unwrap()
+                .output,
+            b"value"
+        );
+        assert_eq!(
+            sim.view(alice(), proxy(), "forward_whoami", vec![])
+                .unwrap()
+                .output,
+            proxy().to_vec()
+        );
+
+        sim.call(alice(), proxy(), "forward_set", vec![]).unwrap();
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"proxied"[..]));
+        assert_eq!(
+            sim.view(alice(), proxy(), "forward_set", vec![]),
+            Err(SimError::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn test_revert_on_panic() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.set_balance(alice(), 100);
+        sim.call(alice(), store, "set", b"value".to_vec()).unwrap();
+        sim.call(alice(), store, "deposit", 40u128.to_le_bytes().to_vec())
+            .unwrap();
+
+        assert!(sim
+            .call(alice(), store, "set_and_fail", b"new".to_vec())
+            .is_err());
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+        assert!(sim.events().is_empty());
+        assert_eq!(sim.balance(&alice()), 60);
+        assert_eq!(sim.balance(&store), 40);
+    }
+
+    #[test]
+    fn test_revert_nested_calls() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.deploy_at(proxy(), alice(), &proxy_wasm(store)).unwrap();
+        sim.call(alice(), store, "set", b"value".to_vec()).unwrap();
+
+        // The failed nested call is reverted, the caller continues
+        let outcome = sim
+            .call(alice(), proxy(), "forward_set_and_fail", vec![])
+            .unwrap();
+        assert!(outcome.output.is_empty());
+        assert!(outcome.events.is_empty());
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+
+        // The successful nested call is reverted with the failed caller
+        assert!(matches!(
+            sim.call(alice(), proxy(), "forward_set_then_trap", vec![]),
+            Err(SimError::Trap(_))
+        ));
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+
+        sim.call(alice(), proxy(), "forward_set", vec![]).unwrap();
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"proxied"[..]));
+    }
+
+    #[test]
+    fn test_call_depth() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.deploy_at(proxy(), alice(), &proxy_wasm(store)).unwrap();
+
+        let outcome = sim.call(alice(), proxy(), "recurse", vec![]).unwrap();
+        assert_eq!(outcome.logs.len(), 1);
+        assert_eq!(outcome.logs[0].message, "bottom");
+    }
+
+    #[test]
+    fn test_fees() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.deploy_at(proxy(), alice(), &proxy_wasm(store)).unwrap();
+
+        let fee = sim
+            .call(alice(), store, "set", b"value".to_vec())
+            .unwrap()
+            .fee;
+        assert_eq!(fee.contract, store);
+        assert_eq!(fee.method, "set");
+        // input, read_register, register_len and storage_write
+        assert_eq!(fee.breakdown.host_calls, 400);
+        assert_eq!(fee.breakdown.storage_write, 50 * b"keyvalue".len() as u64);
+        // The input is written to and read from the register
+        assert_eq!(fee.breakdown.registers, 10);
+        assert!(fee.breakdown.instructions > 0);
+        assert_eq!(fee.breakdown.storage_read, 0);
+        assert_eq!(fee.total(), fee.breakdown.total());
+        // The replaced value is written to the evicted register
+        let fee = sim
+            .call(alice(), store, "set", b"value".to_vec())
+            .unwrap()
+            .fee;
+        assert_eq!(fee.breakdown.registers, 15);
+
+        let fee = sim
+            .call(alice(), proxy(), "forward_set", vec![])
+            .unwrap()
+            .fee;
+        assert_eq!(fee.breakdown.call_contract, 5_000 + 1_000);
+        assert_eq!(fee.nested.len(), 1);
+        assert_eq!(fee.nested[0].method, "set");
+        assert_eq!(
+            fee.total(),
+            fee.breakdown.total() + fee.nested[0].breakdown.total()
+        );
+
+        sim.set_costs(CostTable {
+            instruction: 0,
+            host_call: 0,
+            storage_read_byte: 0,
+            storage_write_byte: 1,
+            register_byte: 0,
+            call_contract: 0,
+            call_depth: 0,
+        });
+        let fee = sim
+            .call(alice(), store, "set", b"value".to_vec())
+            .unwrap()
+            .fee;
+        assert_eq!(fee.total(), 8);
+    }
+
+    #[test]
+    fn test_fee_limit() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+        sim.deploy_at(proxy(), alice(), &proxy_wasm(store)).unwrap();
+        sim.call(alice(), store, "set", b"value".to_vec()).unwrap();
+
+        // The fee limit of the nested call is enforced, the caller continues
+        let outcome = sim
+            .call(alice(), proxy(), "forward_set_limited", vec![])
+            .unwrap();
+        assert!(outcome.output.is_empty());
+        assert!(outcome.fee.nested[0].total() <= 500);
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+
+        sim.set_fee_limit(500);
+        assert_eq!(
+            sim.call(alice(), store, "set", b"new".to_vec()),
+            Err(SimError::FeeLimitExceeded)
+        );
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+
+        // Instructions alone exceed the limit
+        sim.set_costs(CostTable {
+            host_call: 0,
+            ..CostTable::default()
+        });
+        sim.set_fee_limit(5);
+        assert_eq!(
+            sim.call(alice(), store, "set", b"new".to_vec()),
+            Err(SimError::FeeLimitExceeded)
+        );
+        assert_eq!(sim.storage(&store, b"key"), Some(&b"value"[..]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut sim = Simulator::new();
+        let store = deploy_store(&mut sim);
+
+        assert!(matches!(
+            sim.deploy(alice(), b"invalid"),
+            Err(SimError::InvalidWasm(_))
+        ));
+        assert_eq!(
+            sim.deploy_at(store, alice(), &wat::parse_str(STORE_WAT).unwrap()),
+            Err(SimError::ContractAlreadyExists(store))
+        );
+        assert_eq!(
+            sim.call(alice(), store, "unknown", vec![]),
+            Err(SimError::MethodNotFound("unknown".to_string()))
+        );
+        assert_eq!(
+            sim.call(alice(), proxy(), "get", vec![]),
+            Err(SimError::ContractNotFound(proxy()))
+        );
+    }
+
+    #[test]
+    fn test_block() {
+        let mut sim = Simulator::new();
+        sim.set_block_number(10);
+        sim.set_block_timestamp(1_000);
+        sim.set_block_hash([1; 32]);
+        sim.advance_blocks(2);
+        sim.advance_time(60);
+
+        assert_eq!(sim.block_number(), 12);
+        assert_eq!(sim.block_timestamp(), 1_060);
+        assert_eq!(sim.block_hash(), [1; 32]);
+    }
+}
@@ -0,0 +1,662 @@
+//! Execution of contract methods. Implements the host functions declared in `l1x-sys`.
+use crate::error::SimError;
+use crate::fee::{CallFee, CostTable, FeeBreakdown};
+use crate::world::{Event, Log, World};
+use borsh::BorshDeserialize;
+use l1x_sdk::contract_interaction::ContractCall;
+use l1x_sdk::types::{Address, Balance, BlockNumber, TimeStamp};
+use std::collections::HashMap;
+use std::sync::Arc;
+use wasmi::core::{Trap, TrapCode};
+use wasmi::{Caller, Config, Engine, Extern, Linker, Memory, Module, Store};
+
+/// The value returned by `current_runtime_version`.
+pub const RUNTIME_VERSION: u64 = 1;
+
+/// Nested `call_contract` calls deeper than this fail.
+pub const MAX_CALL_DEPTH: usize = 16;
+
+/// The module name of the host functions imported by contracts.
+const HOST_MODULE: &str = "env";
+
+/// The context of a contract call.
+#[derive(Clone, Debug)]
+pub(crate) struct CallContext {
+    pub contract: Address,
+    pub caller: Address,
+    pub method: String,
+    pub input: Vec<u8>,
+    pub read_only: bool,
+    pub depth: usize,
+    pub fee_limit: u64,
+}
+
+/// The state of a single contract call. Nested calls have their own frames.
+struct Frame {
+    engines: Arc<[Engine]>,
+    costs: CostTable,
+    world: World,
+    context: CallContext,
+    owner: Address,
+    registers: HashMap<u64, Vec<u8>>,
+    output: Vec<u8>,
+    last_message: Option<String>,
+    fee: CallFee,
+    /// The fuel given to the call
+    fuel: u64,
+    /// The fuel consumed to pay for the host calls rather than the instructions
+    synthetic_fuel: u64,
+}
+
+impl Frame {
+    fn require_not_read_only(&self) -> Result<(), Trap> {
+        if self.context.read_only {
+            Err(SimError::ReadOnly.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn register(&self, register_id: u64) -> Result<&Vec<u8>, Trap> {
+        self.registers
+            .get(&register_id)
+            .ok_or_else(|| invalid_host_call(format!("Register {} is not used", register_id)))
+    }
+
+    /// Updates the fee of the executed instructions and checks the fee limit.
+    ///
+    /// Returns the fuel left beyond what the remaining fee limit pays for.
+    fn update_fee(&mut self, fuel_consumed: u64) -> Result<u64, SimError> {
+        let instructions = fuel_consumed - self.synthetic_fuel;
+        self.fee.breakdown.instructions = instructions.saturating_mul(self.costs.instruction);
+        let remaining = self
+            .context
+            .fee_limit
+            .checked_sub(self.fee.spent())
+            .ok_or(SimError::FeeLimitExceeded)?;
+        let fuel_left = self.fuel - fuel_consumed;
+        Ok(fuel_left.saturating_sub(fuel_for(remaining, &self.costs)))
+    }
+}
+
+/// Returns the fuel that `fee` pays for.
+fn fuel_for(fee: u64, costs: &CostTable) -> u64 {
+    fee.checked_div(costs.instruction).unwrap_or(u64::MAX)
+}
+
+/// Creates the engines used by each call depth.
+///
+/// A host function can't instantiate a module on the engine that executes it, so nested calls
+/// run on the engine of their depth.
+pub(crate) fn engines() -> Arc<[Engine]> {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    (0..=MAX_CALL_DEPTH).map(|_| Engine::new(&config)).collect()
+}
+
+/// Runs `context.method` of `context.contract` and returns its fee and output.
+///
+/// The frame takes `world` for the duration of the call, nested calls take it from their parent
+/// frame the same way. If the call fails, its changes are reverted. The changes of the top-level
+/// call are committed when it succeeds.
+pub(crate) fn execute(
+    engines: &Arc<[Engine]>,
+    costs: CostTable,
+    world: &mut World,
+    context: CallContext,
+) -> (CallFee, Result<Vec<u8>, SimError>) {
+    let fee = CallFee::new(context.contract, context.method.clone(), context.fee_limit);
+    let (module, owner) = match load(engines, world, &context) {
+        Ok(loaded) => loaded,
+        Err(err) => return (fee, Err(err)),
+    };
+    let method = context.method.clone();
+    let engine = &engines[context.depth];
+    let depth = context.depth;
+    let fuel = fuel_for(context.fee_limit, &costs);
+    let checkpoint = world.checkpoint();
+
+    let frame = Frame {
+        engines: engines.clone(),
+        costs,
+        world: std::mem::take(world),
+        context,
+        owner,
+        registers: HashMap::new(),
+        output: Vec::new(),
+        last_message: None,
+        fee,
+        fuel,
+        synthetic_fuel: 0,
+    };
+    let mut store = Store::new(engine, frame);
+    store
+        .add_fuel(fuel)
+        .expect("Fuel metering is enabled by the engines");
+    let result = run(&mut store, &module, &method);
+    let fuel_consumed = store
+        .fuel_consumed()
+        .expect("Fuel metering is enabled by the engines");
+    let mut frame = store.into_data();
+    let fee_limit = frame.update_fee(fuel_consumed);
+    *world = frame.world;
+
+    match result.and(fee_limit) {
+        Ok(_) => {
+            if depth == 0 {
+                world.commit();
+            }
+            (frame.fee, Ok(frame.output))
+        }
+        Err(err) => {
+            world.revert(checkpoint);
+            (frame.fee, Err(err))
+        }
+    }
+}
+
+/// Returns the compiled contract called by `context` and its owner.
+fn load(
+    engines: &[Engine],
+    world: &mut World,
+    context: &CallContext,
+) -> Result<(Arc<Module>, Address), SimError> {
+    if context.depth > MAX_CALL_DEPTH {
+        return Err(SimError::CallDepthExceeded);
+    }
+    let contract = world
+        .contracts
+        .get_mut(&context.contract)
+        .ok_or(SimError::ContractNotFound(context.contract))?;
+    let module = contract
+        .module(engines, context.depth)
+        .map_err(|err| SimError::InvalidWasm(err.to_string()))?;
+    Ok((module, contract.owner))
+}
+
+fn run(store: &mut Store<Frame>, module: &Module, method: &str) -> Result<(), SimError> {
+    let linker = linker(store.engine()).map_err(|err| SimError::Instantiation(err.to_string()))?;
+    let instance = linker
+        .instantiate(&mut *store, module)
+        .and_then(|instance| instance.start(&mut *store))
+        .map_err(|err| SimError::Instantiation(err.to_string()))?;
+    let func = instance
+        .get_typed_func::<(), ()>(&*store, method)
+        .map_err(|_| SimError::MethodNotFound(method.to_string()))?;
+
+    func.call(&mut *store, ()).map_err(|trap| {
+        if matches!(trap.trap_code(), Some(TrapCode::OutOfFuel)) {
+            return SimError::FeeLimitExceeded;
+        }
+        trap.downcast_ref::<SimError>()
+            .cloned()
+            .unwrap_or_else(|| SimError::Trap(trap.to_string()))
+    })
+}
+
+/// Adds the fees recorded by `fee` to the fee of the call and checks the fee limit.
+fn charge(
+    caller: &mut Caller<'_, Frame>,
+    fee: impl FnOnce(&CostTable, &mut FeeBreakdown),
+) -> Result<(), Trap> {
+    let frame = caller.data_mut();
+    fee(&frame.costs, &mut frame.fee.breakdown);
+    let fuel_consumed = caller
+        .fuel_consumed()
+        .expect("Fuel metering is enabled by the engines");
+    let excess_fuel = caller.data_mut().update_fee(fuel_consumed)?;
+    // The fee paid for the host calls can't be spent on the instructions
+    caller.data_mut().synthetic_fuel += excess_fuel;
+    caller
+        .consume_fuel(excess_fuel)
+        .expect("The excess fuel is left");
+    Ok(())
+}
+
+/// Writes `data` to the register, charges the fee of the written bytes and checks the fee limit.
+fn set_register(
+    caller: &mut Caller<'_, Frame>,
+    register_id: u64,
+    data: Vec<u8>,
+) -> Result<(), Trap> {
+    let bytes = data.len() as u64;
+    charge(caller, |costs, fee| {
+        fee.registers += costs.register_byte.saturating_mul(bytes)
+    })?;
+    caller.data_mut().registers.insert(register_id, data);
+    Ok(())
+}
+
+fn host_call(costs: &CostTable, fee: &mut FeeBreakdown) {
+    fee.host_calls += costs.host_call;
+}
+
+fn invalid_host_call(message: String) -> Trap {
+    SimError::InvalidHostCall(message).into()
+}
+
+fn memory(caller: &Caller<'_, Frame>) -> Result<Memory, Trap> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| invalid_host_call("The contract doesn't export memory".to_string()))
+}
+
+fn read_memory(caller: &Caller<'_, Frame>, addr: u64, len: u64) -> Result<Vec<u8>, Trap> {
+    let memory = memory(caller)?;
+    let mut buffer =
+        vec![
+            0;
+            len.try_into()
+                .map_err(|_| invalid_host_call(format!("Length {} is too big", len)))?
+        ];
+    memory
+        .read(caller, addr as usize, &mut buffer)
+        .map_err(|err| invalid_host_call(err.to_string()))?;
+    Ok(buffer)
+}
+
+fn write_memory(caller: &mut Caller<'_, Frame>, addr: u64, data: &[u8]) -> Result<(), Trap> {
+    let memory = memory(caller)?;
+    memory
+        .write(caller, addr as usize, data)
+        .map_err(|err| invalid_host_call(err.to_string()))
+}
+
+/// Writes `data` to the memory at `addr`. Fails if `len` is not the size of `data`.
+fn write_exact(
+    caller: &mut Caller<'_, Frame>,
+    addr: u64,
+    len: u64,
+    data: &[u8],
+) -> Result<(), Trap> {
+    if len != data.len() as u64 {
+        return Err(invalid_host_call(format!(
+            "Expected buffer of {} bytes, got {}",
+            data.len(),
+            len
+        )));
+    }
+    write_memory(caller, addr, data)
+}
+
+fn read_address(caller: &Caller<'_, Frame>, addr: u64, len: u64) -> Result<Address, Trap> {
+    Address::try_from(read_memory(caller, addr, len)?)
+        .map_err(|_| invalid_host_call("Invalid address".to_string()))
+}
+
+fn read_balance(caller: &Caller<'_, Frame>, addr: u64, len: u64) -> Result<Balance, Trap> {
+    let bytes = read_memory(caller, addr, len)?;
+    Ok(Balance::from_le_bytes(bytes.try_into().map_err(|_| {
+        invalid_host_call("Invalid amount".to_string())
+    })?))
+}
+
+/// Defines the host functions declared in `l1x-sys`.
+fn linker(engine: &Engine) -> Result<Linker<Frame>, wasmi::errors::LinkerError> {
+    let mut linker = Linker::new(engine);
+
+    /*
+     * Register API
+     */
+    linker.func_wrap(
+        HOST_MODULE,
+        "read_register",
+        |mut caller: Caller<'_, Frame>, register_id: u64, result_addr: u64| {
+            charge(&mut caller, host_call)?;
+            let data = caller.data().register(register_id)?.clone();
+            charge(&mut caller, |costs, fee| {
+                fee.registers += costs.register_byte.saturating_mul(data.len() as u64)
+            })?;
+            write_memory(&mut caller, result_addr, &data)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "register_len",
+        |mut caller: Caller<'_, Frame>, register_id: u64| -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            Ok(caller
+                .data()
+                .registers
+                .get(&register_id)
+                .map_or(u64::MAX, |data| data.len() as u64))
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "write_register",
+        |mut caller: Caller<'_, Frame>, register_id: u64, data_addr: u64, data_len: u64| {
+            charge(&mut caller, host_call)?;
+            let data = read_memory(&caller, data_addr, data_len)?;
+            set_register(&mut caller, register_id, data)
+        },
+    )?;
+
+    /*
+     * Storage API
+     */
+    linker.func_wrap(
+        HOST_MODULE,
+        "storage_write",
+        |mut caller: Caller<'_, Frame>,
+         key_addr: u64,
+         key_len: u64,
+         value_addr: u64,
+         value_len: u64,
+         evicted_register_id: u64|
+         -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            caller.data().require_not_read_only()?;
+            let key = read_memory(&caller, key_addr, key_len)?;
+            let value = read_memory(&caller, value_addr, value_len)?;
+            let bytes = (key.len() + value.len()) as u64;
+            charge(&mut caller, |costs, fee| {
+                fee.storage_write += costs.storage_write_byte.saturating_mul(bytes)
+            })?;
+            let frame = caller.data_mut();
+            match frame
+                .world
+                .storage_write(frame.context.contract, key, value)
+            {
+                Some(evicted) => {
+                    set_register(&mut caller, evicted_register_id, evicted)?;
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "storage_read",
+        |mut caller: Caller<'_, Frame>,
+         key_addr: u64,
+         key_len: u64,
+         register_id: u64|
+         -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            let key = read_memory(&caller, key_addr, key_len)?;
+            let frame = caller.data();
+            let value = frame
+                .world
+                .storage_read(&frame.context.contract, &key)
+                .cloned();
+            let bytes = (key.len() + value.as_ref().map_or(0, Vec::len)) as u64;
+            charge(&mut caller, |costs, fee| {
+                fee.storage_read += costs.storage_read_byte.saturating_mul(bytes)
+            })?;
+            match value {
+                Some(value) => {
+                    set_register(&mut caller, register_id, value)?;
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "storage_remove",
+        |mut caller: Caller<'_, Frame>,
+         key_addr: u64,
+         key_len: u64,
+         register_id: u64|
+         -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            caller.data().require_not_read_only()?;
+            let key = read_memory(&caller, key_addr, key_len)?;
+            let bytes = key.len() as u64;
+            charge(&mut caller, |costs, fee| {
+                fee.storage_write += costs.storage_write_byte.saturating_mul(bytes)
+            })?;
+            let frame = caller.data_mut();
+            match frame.world.storage_remove(frame.context.contract, &key) {
+                Some(removed) => {
+                    set_register(&mut caller, register_id, removed)?;
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        },
+    )?;
+
+    /*
+     * Context API
+     */
+    linker.func_wrap(
+        HOST_MODULE,
+        "current_runtime_version",
+        |mut caller: Caller<'_, Frame>| -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            Ok(RUNTIME_VERSION)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "input",
+        |mut caller: Caller<'_, Frame>, register_id: u64| {
+            charge(&mut caller, host_call)?;
+            let input = caller.data().context.input.clone();
+            set_register(&mut caller, register_id, input)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "output",
+        |mut caller: Caller<'_, Frame>, output_addr: u64, output_len: u64| {
+            charge(&mut caller, host_call)?;
+            let output = read_memory(&caller, output_addr, output_len)?;
+            caller.data_mut().output = output;
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "contract_owner_address",
+        |mut caller: Caller<'_, Frame>, register_id: u64| {
+            charge(&mut caller, host_call)?;
+            let owner = caller.data().owner.to_vec();
+            set_register(&mut caller, register_id, owner)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "caller_address",
+        |mut caller: Caller<'_, Frame>, register_id: u64| {
+            charge(&mut caller, host_call)?;
+            let address = caller.data().context.caller.to_vec();
+            set_register(&mut caller, register_id, address)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "contract_instance_address",
+        |mut caller: Caller<'_, Frame>, register_id: u64| {
+            charge(&mut caller, host_call)?;
+            let address = caller.data().context.contract.to_vec();
+            set_register(&mut caller, register_id, address)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "block_hash",
+        |mut caller: Caller<'_, Frame>, output_addr: u64, output_len: u64| {
+            charge(&mut caller, host_call)?;
+            let hash = caller.data().world.block.hash;
+            write_exact(&mut caller, output_addr, output_len, &hash)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "block_number",
+        |mut caller: Caller<'_, Frame>, output_addr: u64, output_len: u64| {
+            charge(&mut caller, host_call)?;
+            let number: BlockNumber = caller.data().world.block.number;
+            write_exact(&mut caller, output_addr, output_len, &number.to_le_bytes())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "block_timestamp",
+        |mut caller: Caller<'_, Frame>, output_addr: u64, output_len: u64| {
+            charge(&mut caller, host_call)?;
+            let timestamp: TimeStamp = caller.data().world.block.timestamp;
+            write_exact(
+                &mut caller,
+                output_addr,
+                output_len,
+                &timestamp.to_le_bytes(),
+            )
+        },
+    )?;
+
+    /*
+     * Economics API
+     */
+    linker.func_wrap(
+        HOST_MODULE,
+        "address_balance",
+        |mut caller: Caller<'_, Frame>, address_ptr: u64, address_len: u64, register_id: u64| {
+            charge(&mut caller, host_call)?;
+            let address = read_address(&caller, address_ptr, address_len)?;
+            let balance = caller.data().world.balance(&address);
+            set_register(&mut caller, register_id, balance.to_le_bytes().to_vec())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "transfer_to",
+        |mut caller: Caller<'_, Frame>,
+         to_address_ptr: u64,
+         to_address_len: u64,
+         amount_ptr: u64,
+         amount_len: u64|
+         -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            caller.data().require_not_read_only()?;
+            let to = read_address(&caller, to_address_ptr, to_address_len)?;
+            let amount = read_balance(&caller, amount_ptr, amount_len)?;
+            let frame = caller.data_mut();
+            let from = frame.context.contract;
+            Ok(frame.world.transfer(from, to, amount) as u64)
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "transfer_from_caller",
+        |mut caller: Caller<'_, Frame>, amount_ptr: u64, amount_len: u64| -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            caller.data().require_not_read_only()?;
+            let amount = read_balance(&caller, amount_ptr, amount_len)?;
+            let frame = caller.data_mut();
+            let (from, to) = (frame.context.caller, frame.context.contract);
+            Ok(frame.world.transfer(from, to, amount) as u64)
+        },
+    )?;
+
+    /*
+     * Misc API
+     */
+    linker.func_wrap(
+        HOST_MODULE,
+        "panic",
+        |mut caller: Caller<'_, Frame>| -> Result<(), Trap> {
+            charge(&mut caller, host_call)?;
+            let frame = caller.data();
+            Err(SimError::Panic {
+                contract: frame.context.contract,
+                message: frame.last_message.clone(),
+            }
+            .into())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "msg",
+        |mut caller: Caller<'_, Frame>, addr: u64, len: u64| {
+            charge(&mut caller, host_call)?;
+            let message = String::from_utf8_lossy(&read_memory(&caller, addr, len)?).into_owned();
+            let frame = caller.data_mut();
+            frame.world.logs.push(Log {
+                contract: frame.context.contract,
+                message: message.clone(),
+            });
+            frame.last_message = Some(message);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "call_contract",
+        |mut caller: Caller<'_, Frame>,
+         call_addr: u64,
+         len: u64,
+         result_register_id: u64|
+         -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            let call = ContractCall::try_from_slice(&read_memory(&caller, call_addr, len)?)
+                .map_err(|_| invalid_host_call("Can't deserialize the call".to_string()))?;
+            if !call.read_only {
+                caller.data().require_not_read_only()?;
+            }
+            let depth = caller.data().context.depth + 1;
+            charge(&mut caller, |costs, fee| {
+                fee.call_contract += costs
+                    .call_contract
+                    .saturating_add(costs.call_depth.saturating_mul(depth as u64))
+            })?;
+            let frame = caller.data_mut();
+            // The nested call can't spend more than its caller has left
+            let remaining = frame
+                .context
+                .fee_limit
+                .checked_sub(frame.fee.spent())
+                .ok_or(SimError::FeeLimitExceeded)?;
+            let context = CallContext {
+                contract: call.contract_address,
+                caller: frame.context.contract,
+                method: call.method_name,
+                input: call.args,
+                read_only: frame.context.read_only || call.read_only,
+                depth,
+                fee_limit: remaining.min(call.fee_limit.try_into().unwrap_or(u64::MAX)),
+            };
+            let (fee, result) = execute(
+                &frame.engines.clone(),
+                frame.costs,
+                &mut frame.world,
+                context,
+            );
+            frame.fee.nested.push(fee);
+            // The caller pays for the nested call, even if it failed
+            match result {
+                Ok(output) => {
+                    set_register(&mut caller, result_register_id, output)?;
+                    Ok(1)
+                }
+                Err(_) => {
+                    charge(&mut caller, |_, _| ())?;
+                    Ok(0)
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "emit_event_experimental",
+        |mut caller: Caller<'_, Frame>, data_addr: u64, len: u64| -> Result<u64, Trap> {
+            charge(&mut caller, host_call)?;
+            caller.data().require_not_read_only()?;
+            let data = read_memory(&caller, data_addr, len)?;
+            let frame = caller.data_mut();
+            frame.world.events.push(Event {
+                contract: frame.context.contract,
+                data,
+            });
+            Ok(1)
+        },
+    )?;
+
+    Ok(linker)
+}
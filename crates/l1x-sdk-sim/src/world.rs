@@ -0,0 +1,206 @@
+use borsh::BorshDeserialize;
+use l1x_sdk::types::{Address, Balance, BlockHash, BlockNumber, TimeStamp};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// A message written by a contract with `msg`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Log {
+    /// The contract that wrote the message
+    pub contract: Address,
+    pub message: String,
+}
+
+/// An event emitted by a contract with `emit_event_experimental`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// The contract that emitted the event
+    pub contract: Address,
+    /// Borsh serialized event
+    pub data: Vec<u8>,
+}
+
+impl Event {
+    /// Deserializes the event data.
+    pub fn decode<T: BorshDeserialize>(&self) -> std::io::Result<T> {
+        T::try_from_slice(&self.data)
+    }
+}
+
+/// The current block.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Block {
+    pub number: BlockNumber,
+    pub timestamp: TimeStamp,
+    pub hash: BlockHash,
+}
+
+/// A deployed contract.
+#[derive(Clone)]
+pub(crate) struct Contract {
+    pub wasm: Arc<[u8]>,
+    /// The contract compiled for the engine of each call depth
+    pub modules: Vec<Option<Arc<wasmi::Module>>>,
+    pub owner: Address,
+    pub storage: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Contract {
+    /// Returns the contract compiled for `engines[depth]`.
+    pub fn module(
+        &mut self,
+        engines: &[wasmi::Engine],
+        depth: usize,
+    ) -> Result<Arc<wasmi::Module>, wasmi::Error> {
+        if self.modules.len() <= depth {
+            self.modules.resize(depth + 1, None);
+        }
+        if let Some(module) = &self.modules[depth] {
+            return Ok(module.clone());
+        }
+        let module = Arc::new(wasmi::Module::new(&engines[depth], &self.wasm[..])?);
+        self.modules[depth] = Some(module.clone());
+        Ok(module)
+    }
+}
+
+/// A change that can be reverted.
+#[derive(Clone, Debug)]
+enum JournalEntry {
+    Storage {
+        contract: Address,
+        key: Vec<u8>,
+        previous: Option<Vec<u8>>,
+    },
+    Balance {
+        address: Address,
+        previous: Option<Balance>,
+    },
+}
+
+/// The state of [`World`] that [`World::revert`] returns to.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Checkpoint {
+    journal_len: usize,
+    events_len: usize,
+}
+
+/// The state shared by all contracts.
+///
+/// Storage and balance changes are journaled until [`World::commit`], so the changes of a failed
+/// call, including its nested calls, can be reverted.
+#[derive(Clone, Default)]
+pub(crate) struct World {
+    pub contracts: HashMap<Address, Contract>,
+    pub balances: HashMap<Address, Balance>,
+    pub block: Block,
+    pub logs: Vec<Log>,
+    pub events: Vec<Event>,
+    journal: Vec<JournalEntry>,
+}
+
+impl World {
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            journal_len: self.journal.len(),
+            events_len: self.events.len(),
+        }
+    }
+
+    /// Reverts the storage and balance changes and removes the events made after `checkpoint`.
+    ///
+    /// Logs are kept, so the reason of the failure can be inspected.
+    pub fn revert(&mut self, checkpoint: Checkpoint) {
+        while self.journal.len() > checkpoint.journal_len {
+            match self.journal.pop().expect("The journal is not empty") {
+                JournalEntry::Storage {
+                    contract,
+                    key,
+                    previous,
+                } => {
+                    let storage = &mut self
+                        .contracts
+                        .get_mut(&contract)
+                        .expect("The contract of the journal entry exists")
+                        .storage;
+                    match previous {
+                        Some(previous) => storage.insert(key, previous),
+                        None => storage.remove(&key),
+                    };
+                }
+                JournalEntry::Balance { address, previous } => {
+                    match previous {
+                        Some(previous) => self.balances.insert(address, previous),
+                        None => self.balances.remove(&address),
+                    };
+                }
+            }
+        }
+        self.events.truncate(checkpoint.events_len);
+    }
+
+    /// Makes the journaled changes permanent.
+    pub fn commit(&mut self) {
+        self.journal.clear();
+    }
+
+    pub fn storage_read(&self, contract: &Address, key: &[u8]) -> Option<&Vec<u8>> {
+        self.contracts.get(contract)?.storage.get(key)
+    }
+
+    /// Writes key-value into the storage of `contract`. Returns the replaced value.
+    pub fn storage_write(
+        &mut self,
+        contract: Address,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        let previous = self.storage_mut(&contract).insert(key.clone(), value);
+        self.journal.push(JournalEntry::Storage {
+            contract,
+            key,
+            previous: previous.clone(),
+        });
+        previous
+    }
+
+    /// Removes the key from the storage of `contract`. Returns the removed value.
+    pub fn storage_remove(&mut self, contract: Address, key: &[u8]) -> Option<Vec<u8>> {
+        let previous = self.storage_mut(&contract).remove(key)?;
+        self.journal.push(JournalEntry::Storage {
+            contract,
+            key: key.to_vec(),
+            previous: Some(previous.clone()),
+        });
+        Some(previous)
+    }
+
+    fn storage_mut(&mut self, contract: &Address) -> &mut BTreeMap<Vec<u8>, Vec<u8>> {
+        &mut self
+            .contracts
+            .get_mut(contract)
+            .expect("The called contract exists")
+            .storage
+    }
+
+    fn set_balance(&mut self, address: Address, balance: Balance) {
+        let previous = self.balances.insert(address, balance);
+        self.journal
+            .push(JournalEntry::Balance { address, previous });
+    }
+
+    pub fn balance(&self, address: &Address) -> Balance {
+        self.balances.get(address).copied().unwrap_or_default()
+    }
+
+    /// Moves `amount` from `from` to `to`. Returns `false` if `from` doesn't have enough funds.
+    pub fn transfer(&mut self, from: Address, to: Address, amount: Balance) -> bool {
+        let Some(from_balance) = self.balance(&from).checked_sub(amount) else {
+            return false;
+        };
+        self.set_balance(from, from_balance);
+        let to_balance = self.balance(&to).saturating_add(amount);
+        self.set_balance(to, to_balance);
+        true
+    }
+}
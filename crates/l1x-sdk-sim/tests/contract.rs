@@ -0,0 +1,244 @@
+//! End-to-end tests of contracts built with `l1x-sdk`.
+//!
+//! The contracts in `tests/fixtures` are built for `wasm32-unknown-unknown` when a test needs
+//! them, so the target must be installed: `rustup target add wasm32-unknown-unknown`.
+use borsh::BorshSerialize;
+use l1x_sdk::types::Address;
+use l1x_sdk_sim::{SimError, Simulator};
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+fn owner() -> Address {
+    Address::from([1; 20])
+}
+
+/// Builds the fixture contract `name` and returns the binary.
+fn build_fixture(name: &str) -> Vec<u8> {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("../../target/fixtures");
+    let output = Command::new(env!("CARGO"))
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+        .arg("--manifest-path")
+        .arg(
+            manifest_dir
+                .join("tests/fixtures")
+                .join(name)
+                .join("Cargo.toml"),
+        )
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .output()
+        .expect("Failed to run cargo");
+    assert!(
+        output.status.success(),
+        "Failed to build {}:\n{}",
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let wasm = target_dir
+        .join("wasm32-unknown-unknown/release")
+        .join(name)
+        .with_extension("wasm");
+    std::fs::read(wasm).expect("Failed to read the built contract")
+}
+
+fn counter_wasm() -> &'static [u8] {
+    static WASM: OnceLock<Vec<u8>> = OnceLock::new();
+    WASM.get_or_init(|| build_fixture("counter"))
+}
+
+fn get(sim: &mut Simulator, counter: Address) -> u64 {
+    sim.view_json(owner(), counter, "get", &())
+        .unwrap()
+        .json()
+        .unwrap()
+}
+
+#[test]
+fn test_counter() {
+    let mut sim = Simulator::new();
+    let counter = sim.deploy(owner(), counter_wasm()).unwrap();
+
+    assert!(matches!(
+        sim.view_json(owner(), counter, "get", &()),
+        Err(SimError::Panic { message: Some(message), .. }) if message.contains("not initialized")
+    ));
+    sim.call_json(owner(), counter, "new", &json!({ "value": 1 }))
+        .unwrap();
+    assert_eq!(
+        sim.call_json(owner(), counter, "new", &json!({ "value": 1 })),
+        Err(SimError::Panic {
+            contract: counter,
+            message: Some("The contract has already been initialized".to_string())
+        })
+    );
+
+    let outcome = sim
+        .call_json(owner(), counter, "increment", &json!({ "by": 2 }))
+        .unwrap();
+    assert_eq!(outcome.json::<u64>().unwrap(), 3);
+    assert_eq!(get(&mut sim, counter), 3);
+
+    // The state can't be modified in a read-only call
+    assert_eq!(
+        sim.view_json(owner(), counter, "increment", &json!({ "by": 2 })),
+        Err(SimError::ReadOnly)
+    );
+    assert_eq!(get(&mut sim, counter), 3);
+}
+
+#[test]
+fn test_counter_wrappers() {
+    let mut sim = Simulator::new();
+    let counter = sim.deploy(owner(), counter_wasm()).unwrap();
+    let other = Address::from([2; 20]);
+    sim.call_json(owner(), counter, "new", &json!({ "value": 1 }))
+        .unwrap();
+
+    // Borsh arguments and return value
+    let outcome = sim
+        .view(owner(), counter, "add", 2u64.try_to_vec().unwrap())
+        .unwrap();
+    assert_eq!(outcome.borsh::<u64>().unwrap(), 3);
+
+    // The deposit is transferred from the caller before the method is called
+    sim.set_balance(other, 100);
+    let outcome = sim
+        .call_json(other, counter, "buy", &json!({ "amount": "40" }))
+        .unwrap();
+    assert_eq!(outcome.json::<u64>().unwrap(), 41);
+    assert_eq!(sim.balance(&other), 60);
+    assert_eq!(sim.balance(&counter), 40);
+
+    assert_eq!(
+        sim.call_json(other, counter, "reset", &()),
+        Err(SimError::Panic {
+            contract: counter,
+            message: Some("The method can only be called by the contract owner".to_string())
+        })
+    );
+    assert_eq!(
+        sim.call_json(owner(), counter, "set", &json!({ "value": 7 })),
+        Err(SimError::Panic {
+            contract: counter,
+            message: Some("The method can only be called by the contract itself".to_string())
+        })
+    );
+    assert_eq!(get(&mut sim, counter), 41);
+    sim.call_json(owner(), counter, "reset", &()).unwrap();
+    assert_eq!(get(&mut sim, counter), 0);
+
+    // The fixture is built with the `abi` feature
+    let abi: serde_json::Value = sim
+        .view(owner(), counter, "__l1x_abi_buy", Vec::new())
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(abi["name"], "buy");
+    assert_eq!(abi["payable"], true);
+    assert_eq!(abi["args"][0]["deposit"], true);
+}
+
+#[test]
+fn test_counter_abi() {
+    let mut sim = Simulator::new();
+    let counter = sim.deploy(owner(), counter_wasm()).unwrap();
+
+    let abi: serde_json::Value = sim
+        .view(owner(), counter, "__l1x_abi", Vec::new())
+        .unwrap()
+        .json()
+        .unwrap();
+    let methods: Vec<_> = abi["methods"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|method| method["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        methods,
+        [
+            "new",
+            "get",
+            "increment",
+            "decrement",
+            "add",
+            "buy",
+            "reset",
+            "set",
+            "decrement_other"
+        ]
+    );
+    assert_eq!(abi["methods"][0]["kind"], "init");
+    assert_eq!(abi["methods"][4]["args_serializer"], "borsh");
+}
+
+#[test]
+fn test_counter_client() {
+    let mut sim = Simulator::new();
+    let caller = sim.deploy(owner(), counter_wasm()).unwrap();
+    let counter = sim.deploy(owner(), counter_wasm()).unwrap();
+    sim.call_json(owner(), caller, "new", &json!({ "value": 0 }))
+        .unwrap();
+    sim.call_json(owner(), counter, "new", &json!({ "value": 5 }))
+        .unwrap();
+
+    // The client deserializes the `Ok` value of the `#[handle_result]` method
+    let outcome = sim
+        .call_json(
+            owner(),
+            caller,
+            "decrement_other",
+            &json!({ "counter": counter, "by": 2 }),
+        )
+        .unwrap();
+    assert_eq!(outcome.json::<Option<u64>>().unwrap(), Some(3));
+    assert_eq!(get(&mut sim, counter), 3);
+
+    // The `Err` value fails the call, so the client returns `None`
+    let outcome = sim
+        .call_json(
+            owner(),
+            caller,
+            "decrement_other",
+            &json!({ "counter": counter, "by": 10 }),
+        )
+        .unwrap();
+    assert_eq!(outcome.json::<Option<u64>>().unwrap(), None);
+    assert_eq!(get(&mut sim, counter), 3);
+}
+
+#[test]
+fn test_counter_fees() {
+    let mut sim = Simulator::new();
+    let counter = sim.deploy(owner(), counter_wasm()).unwrap();
+    sim.call_json(owner(), counter, "new", &json!({ "value": 1 }))
+        .unwrap();
+
+    let fee = sim
+        .call_json(owner(), counter, "increment", &json!({ "by": 2 }))
+        .unwrap()
+        .fee;
+    assert_eq!(fee.contract, counter);
+    assert_eq!(fee.method, "increment");
+    assert!(fee.breakdown.instructions > 0);
+    assert!(fee.breakdown.host_calls > 0);
+    assert!(fee.breakdown.storage_read > 0);
+    assert!(fee.breakdown.storage_write > 0);
+    assert!(fee.nested.is_empty());
+    assert_eq!(fee.total(), fee.breakdown.total());
+
+    let view_fee = sim.view_json(owner(), counter, "get", &()).unwrap().fee;
+    assert_eq!(view_fee.breakdown.storage_write, 0);
+    assert!(view_fee.total() < fee.total());
+
+    sim.set_fee_limit(fee.total() / 2);
+    assert_eq!(
+        sim.call_json(owner(), counter, "increment", &json!({ "by": 2 })),
+        Err(SimError::FeeLimitExceeded)
+    );
+    sim.set_fee_limit(u64::MAX);
+    assert_eq!(get(&mut sim, counter), 3);
+}
@@ -0,0 +1,71 @@
+//! A contract used by the integration tests of the simulator.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::types::{Address, U128};
+use l1x_sdk::{contract, ext_contract};
+
+/// Fee limit of the calls to other counters.
+const FEE_LIMIT: u128 = 1_000_000;
+
+#[ext_contract]
+trait CounterContract {
+    #[handle_result]
+    fn decrement(&mut self, by: u64) -> Result<u64, String>;
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Counter {
+    value: u64,
+}
+
+#[contract]
+impl Counter {
+    #[init]
+    pub fn new(value: u64) -> Self {
+        Self { value }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+
+    pub fn increment(&mut self, by: u64) -> u64 {
+        self.value += by;
+        self.value
+    }
+
+    #[handle_result]
+    pub fn decrement(&mut self, by: u64) -> Result<u64, String> {
+        self.value = self
+            .value
+            .checked_sub(by)
+            .ok_or_else(|| "Counter underflow".to_string())?;
+        Ok(self.value)
+    }
+
+    #[args(borsh)]
+    #[result_serializer(borsh)]
+    pub fn add(&self, other: u64) -> u64 {
+        self.value + other
+    }
+
+    #[payable]
+    pub fn buy(&mut self, #[deposit] amount: U128) -> u64 {
+        self.value += u128::from(amount) as u64;
+        self.value
+    }
+
+    #[only_owner]
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+
+    #[private]
+    pub fn set(&mut self, value: u64) {
+        self.value = value;
+    }
+
+    /// Decrements the counter deployed at `counter` and returns its new value.
+    pub fn decrement_other(&mut self, counter: Address, by: u64) -> Option<u64> {
+        CounterContractClient::new(counter, FEE_LIMIT).decrement(by)
+    }
+}
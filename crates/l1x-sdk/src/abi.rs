@@ -0,0 +1,142 @@
+//! Types describing the contract ABI.
+//!
+//! When the `abi` feature is enabled, [`crate::contract`] exports `__l1x_abi` function that writes
+//! the JSON serialized [`AbiContract`] with [`crate::output`]. It describes all methods of the
+//! contract. The description of a single method is written as [`AbiMethod`] by
+//! `__l1x_abi_<method>` function.
+//!
+//! JSON serialized arguments and return values must implement [`JsonSchema`]. The derive macro can
+//! be used through the re-exported crate: `#[derive(JsonSchema)]` with
+//! `#[schemars(crate = "l1x_sdk::abi::schemars")]`.
+//!
+//! The feature should be disabled when the contract is built for deployment.
+pub use schemars;
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Description of the methods exported by the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AbiContract {
+    /// Methods of the contract in the declaration order
+    pub methods: Vec<AbiMethod>,
+}
+
+/// Description of an exported contract method.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AbiMethod {
+    /// The name of the method
+    pub name: String,
+    /// The kind of the method
+    pub kind: AbiMethodKind,
+    /// `true` if the method transfers the deposit argument from the caller
+    pub payable: bool,
+    /// `true` if the method can only be called by the contract owner
+    pub only_owner: bool,
+    /// `true` if the method can only be called by the contract itself
+    pub private: bool,
+    /// The serializer of the method arguments
+    pub args_serializer: AbiSerializer,
+    /// Arguments of the method in the declaration order
+    pub args: Vec<AbiArg>,
+    /// The serializer of the return value
+    pub result_serializer: AbiSerializer,
+    /// The type of the return value. `None` if the method doesn't write the output.
+    pub result: Option<AbiType>,
+}
+
+/// The kind of a contract method.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AbiMethodKind {
+    /// `#[init]` method that creates the initial contract state
+    Init,
+    /// Read-only method that can be called with `read_only: true`
+    View,
+    /// Method that can modify the state
+    Call,
+}
+
+/// Serialization format of arguments or return values.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AbiSerializer {
+    Json,
+    Borsh,
+}
+
+/// Description of a method argument.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AbiArg {
+    /// The name of the argument
+    pub name: String,
+    /// The type of the argument
+    #[serde(flatten)]
+    pub ty: AbiType,
+    /// `true` if the argument is the amount transferred from the caller by `#[payable]` method
+    pub deposit: bool,
+}
+
+/// Description of a type used in the contract ABI.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AbiType {
+    /// The type as it's written in the Rust source
+    pub type_name: String,
+    /// JSON schema of the type. Only provided for JSON serialized values.
+    pub schema: Option<RootSchema>,
+}
+
+impl AbiType {
+    /// Describes a JSON serialized type.
+    pub fn json<T: JsonSchema>(type_name: &str) -> Self {
+        Self {
+            type_name: type_name.to_string(),
+            schema: Some(schemars::schema_for!(T)),
+        }
+    }
+
+    /// Describes a Borsh serialized type.
+    pub fn borsh(type_name: &str) -> Self {
+        Self {
+            type_name: type_name.to_string(),
+            schema: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::U128;
+
+    #[test]
+    fn test_abi_method_serialization() {
+        let method = AbiMethod {
+            name: "transfer".to_string(),
+            kind: AbiMethodKind::Call,
+            payable: true,
+            only_owner: false,
+            private: false,
+            args_serializer: AbiSerializer::Json,
+            args: vec![AbiArg {
+                name: "amount".to_string(),
+                ty: AbiType::json::<U128>("U128"),
+                deposit: true,
+            }],
+            result_serializer: AbiSerializer::Borsh,
+            result: Some(AbiType::borsh("u64")),
+        };
+
+        let json = serde_json::to_value(&method).unwrap();
+        assert_eq!(json["kind"], "call");
+        assert_eq!(json["args"][0]["name"], "amount");
+        assert_eq!(json["args"][0]["type_name"], "U128");
+        assert_eq!(json["args"][0]["schema"]["type"], "string");
+        assert_eq!(json["args"][0]["deposit"], true);
+        assert_eq!(json["result_serializer"], "borsh");
+        assert_eq!(json["result"]["schema"], serde_json::Value::Null);
+
+        let deserialized: AbiMethod = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, method);
+    }
+}
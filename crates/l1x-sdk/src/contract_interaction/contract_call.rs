@@ -1,14 +1,15 @@
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::types;
 
-#[derive(BorshSerialize)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ContractCall {
     /// The target contract address
     pub contract_address: types::Address,
     /// The method should be called in the target contract
     pub method_name: String,
-    /// JSON serialized arguments that will be passed to the method.
+    /// Serialized arguments that will be passed to the method. Arguments are JSON serialized unless
+    /// the method uses Borsh arguments.
     pub args: Vec<u8>,
     /// Set `true` if this call should be read-only.
     pub read_only: bool,
@@ -0,0 +1,185 @@
+//! Registers keep data on the host side, so it can be passed between host calls without copying
+//! it through the contract memory.
+//!
+//! The SDK reserves [`EVICTED_REGISTER`] and [`ATOMIC_OP_REGISTER`] for its own host calls. Use
+//! [`Register::alloc`] to get a register that is not used by the SDK or other allocated registers.
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+pub type RegisterId = u64;
+
+/// The register that receives values evicted by [`crate::storage_write`] and
+/// [`crate::storage_remove`].
+pub const EVICTED_REGISTER: RegisterId = std::u64::MAX - 1;
+/// The register that receives the results of the other host calls made by the SDK.
+pub const ATOMIC_OP_REGISTER: RegisterId = std::u64::MAX - 2;
+
+const ERR_RESERVED_REGISTER: &str = "The register is reserved by the SDK";
+
+thread_local! {
+    static ALLOCATED: RefCell<BTreeSet<RegisterId>> = RefCell::new(BTreeSet::new());
+}
+
+/// Returns `true` if the register is used by the SDK.
+pub fn is_reserved(register_id: RegisterId) -> bool {
+    register_id == EVICTED_REGISTER || register_id == ATOMIC_OP_REGISTER
+}
+
+/// Returns the size of the register. If register is not used returns `None`.
+pub fn len(register_id: RegisterId) -> Option<u64> {
+    #[cfg(any(test, feature = "mock"))]
+    {
+        crate::testing::host::register_len(register_id)
+    }
+    #[cfg(not(any(test, feature = "mock")))]
+    {
+        let len = unsafe { l1x_sys::register_len(register_id) };
+        if len == std::u64::MAX {
+            None
+        } else {
+            Some(len)
+        }
+    }
+}
+
+/// Reads the content of the `register_id`. If register is not used returns `None`.
+pub fn read(register_id: RegisterId) -> Option<Vec<u8>> {
+    #[cfg(any(test, feature = "mock"))]
+    {
+        crate::testing::host::read_register(register_id)
+    }
+    #[cfg(not(any(test, feature = "mock")))]
+    {
+        let len: usize = len(register_id)?
+            .try_into()
+            .unwrap_or_else(|_| crate::abort());
+
+        let mut buffer = Vec::with_capacity(len);
+
+        unsafe {
+            l1x_sys::read_register(register_id, buffer.as_mut_ptr() as u64);
+
+            buffer.set_len(len);
+        }
+        Some(buffer)
+    }
+}
+
+/// Writes `data` to the register.
+///
+/// # Panics
+/// If the register is reserved by the SDK, see [`is_reserved`].
+pub fn write(register_id: RegisterId, data: &[u8]) {
+    if is_reserved(register_id) {
+        crate::panic(ERR_RESERVED_REGISTER);
+    }
+    #[cfg(any(test, feature = "mock"))]
+    crate::testing::host::write_register(register_id, data);
+    #[cfg(not(any(test, feature = "mock")))]
+    unsafe {
+        l1x_sys::write_register(register_id, data.as_ptr() as _, data.len() as _)
+    }
+}
+
+/// A register allocated for the exclusive use of its owner. The register is released when it's
+/// dropped.
+///
+/// Registers are allocated from the lowest ids, so they never clash with the registers reserved
+/// by the SDK.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Register {
+    id: RegisterId,
+}
+
+impl Register {
+    /// Allocates the lowest register id that is not allocated.
+    pub fn alloc() -> Self {
+        let id = ALLOCATED.with(|allocated| {
+            let mut allocated = allocated.borrow_mut();
+            let id = (0..)
+                .zip(allocated.iter())
+                .find(|(id, allocated_id)| id != *allocated_id)
+                .map_or(allocated.len() as RegisterId, |(id, _)| id);
+            allocated.insert(id);
+            id
+        });
+        Self { id }
+    }
+
+    /// Returns the id of the register, e.g. to pass it to the host functions of [`l1x_sys`].
+    pub fn id(&self) -> RegisterId {
+        self.id
+    }
+
+    /// See [`len`].
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> Option<u64> {
+        len(self.id)
+    }
+
+    /// See [`read`].
+    pub fn read(&self) -> Option<Vec<u8>> {
+        read(self.id)
+    }
+
+    /// See [`write`].
+    pub fn write(&self, data: &[u8]) {
+        write(self.id, data)
+    }
+}
+
+impl Drop for Register {
+    fn drop(&mut self) {
+        // The allocator may be already destroyed if the register is dropped at the thread exit
+        let _ = ALLOCATED.try_with(|allocated| allocated.borrow_mut().remove(&self.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::reset_env;
+
+    #[test]
+    fn test_read_write() {
+        reset_env();
+
+        assert_eq!(len(1), None);
+        assert_eq!(read(1), None);
+
+        write(1, b"data");
+        assert_eq!(len(1), Some(4));
+        assert_eq!(read(1), Some(b"data".to_vec()));
+
+        write(1, b"");
+        assert_eq!(read(1), Some(Vec::new()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The register is reserved by the SDK")]
+    fn test_write_reserved() {
+        reset_env();
+        write(ATOMIC_OP_REGISTER, b"data");
+    }
+
+    #[test]
+    fn test_alloc() {
+        reset_env();
+        let first = Register::alloc();
+        let second = Register::alloc();
+        let third = Register::alloc();
+        assert_eq!((first.id(), second.id(), third.id()), (0, 1, 2));
+
+        // Released ids are reused
+        drop(second);
+        let fourth = Register::alloc();
+        assert_eq!(fourth.id(), 1);
+        assert_eq!(Register::alloc().id(), 3);
+
+        first.write(b"first");
+        fourth.write(b"fourth");
+        assert_eq!(first.read(), Some(b"first".to_vec()));
+        assert_eq!(fourth.read(), Some(b"fourth".to_vec()));
+        assert!(!is_reserved(fourth.id()));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::CacheEntry;
+
+/// A view into a single entry of [`super::LookupMap`], which may either be vacant or occupied.
+///
+/// Created by [`super::LookupMap::entry`].
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Returns a reference to the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if it's vacant, and returns a
+    /// mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` if it's vacant, and
+    /// returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` called with the key if
+    /// it's vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if it's vacant, and returns
+    /// a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Modifies the value of an occupied entry. Vacant entries are returned unchanged.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Self::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Self::Occupied(entry)
+            }
+            Self::Vacant(entry) => Self::Vacant(entry),
+        }
+    }
+}
+
+/// A view into an occupied entry of [`super::LookupMap`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V> {
+    pub(super) key: K,
+    pub(super) entry: &'a mut CacheEntry<V>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Returns a reference to the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a reference to the value of the entry.
+    pub fn get(&self) -> &V {
+        self.entry
+            .value()
+            .as_ref()
+            .unwrap_or_else(|| crate::abort())
+    }
+
+    /// Returns a mutable reference to the value of the entry. The value is written to the storage
+    /// on flush.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.entry
+            .value_mut()
+            .as_mut()
+            .unwrap_or_else(|| crate::abort())
+    }
+
+    /// Converts the entry into a mutable reference to its value, bound to the lifetime of the map.
+    pub fn into_mut(self) -> &'a mut V {
+        self.entry
+            .value_mut()
+            .as_mut()
+            .unwrap_or_else(|| crate::abort())
+    }
+
+    /// Sets the value of the entry and returns the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        self.entry
+            .replace(Some(value))
+            .unwrap_or_else(|| crate::abort())
+    }
+
+    /// Removes the entry from the map and returns its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes the entry from the map and returns its key and value.
+    pub fn remove_entry(self) -> (K, V) {
+        let value = self.entry.replace(None).unwrap_or_else(|| crate::abort());
+        (self.key, value)
+    }
+}
+
+/// A view into a vacant entry of [`super::LookupMap`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V> {
+    pub(super) key: K,
+    pub(super) entry: &'a mut CacheEntry<V>,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// Returns a reference to the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.entry.replace(Some(value));
+        self.entry
+            .value_mut()
+            .as_mut()
+            .unwrap_or_else(|| crate::abort())
+    }
+}
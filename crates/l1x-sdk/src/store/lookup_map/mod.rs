@@ -1,6 +1,9 @@
 //! An implementation of a map that stores its content directly on the persistent storage.
+mod entry;
 mod impls;
 
+pub use self::entry::{Entry, OccupiedEntry, VacantEntry};
+
 use crate::utils::{EntryState, StableMap};
 use crate::CacheEntry;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -144,34 +147,54 @@ where
         K: Borrow<Q>,
         Q: BorshSerialize + ToOwned<Owned = K>,
     {
-        let cached = self.cache.get_mut(k.to_owned());
-        cached.value.get_or_init(|| {
-            let (key, value) = Self::load_element(&self.prefix, k);
-            let _ = cached.hash.set(key);
-            CacheEntry::new_cached(value)
-        });
-
-        let entry = cached.value.get_mut().unwrap_or_else(|| crate::abort());
+        let entry = self.get_mut_inner(k);
         match entry.value() {
             Some(_) => Some(entry.value_mut().as_mut().unwrap_or_else(|| crate::abort())),
             None => None,
         }
     }
 
+    /// Returns the cached entry of the key, loading it from the storage if it's not cached.
     pub(crate) fn get_mut_inner<Q: ?Sized>(&mut self, k: &Q) -> &mut CacheEntry<V>
     where
         K: Borrow<Q>,
         Q: BorshSerialize + ToOwned<Owned = K>,
     {
-        let prefix = &self.prefix;
-        let entry = self.cache.get_mut(k.to_owned());
-        entry.value.get_or_init(|| {
-            let (key, value) = Self::load_element(prefix, k);
-            let _ = entry.hash.set(key);
+        let cached = self.cache.get_mut(k.to_owned());
+        cached.value.get_or_init(|| {
+            let (key, value) = Self::load_element(&self.prefix, k);
+            let _ = cached.hash.set(key);
             CacheEntry::new_cached(value)
         });
-        let entry = entry.value.get_mut().unwrap_or_else(|| crate::abort());
-        entry
+        cached.value.get_mut().unwrap_or_else(|| crate::abort())
+    }
+
+    /// Gets the entry of the key for in-place manipulation.
+    ///
+    /// The value is read from the storage once, when the entry is created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l1x_sdk::store::LookupMap;
+    ///
+    /// fn count(counters: &mut LookupMap<String, u64>, name: &str) {
+    ///     counters
+    ///         .entry(name.to_string())
+    ///         .and_modify(|counter| *counter += 1)
+    ///         .or_insert(1);
+    /// }
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<K, V>
+    where
+        K: Clone,
+    {
+        let entry = self.get_mut_inner(&key);
+        if entry.value().is_some() {
+            Entry::Occupied(OccupiedEntry { key, entry })
+        } else {
+            Entry::Vacant(VacantEntry { key, entry })
+        }
     }
 
     /// Inserts a key-value pair into the map.
@@ -179,19 +202,48 @@ where
     /// If the map did not have this key present, None is returned.
     ///
     /// If the map did have this key present, the value is updated, and the old value is returned.
+    ///
+    /// If the key is not cached, the value is written to the storage immediately, so the old value
+    /// is returned by the host instead of being read from the storage.
     pub fn insert(&mut self, k: K, v: V) -> Option<V>
     where
         K: Clone,
     {
-        self.get_mut_inner(&k).replace(Some(v))
+        let prefix = &self.prefix;
+        let cached = self.cache.get_mut(k.clone());
+        if let Some(entry) = cached.value.get_mut() {
+            return entry.replace(Some(v));
+        }
+        let key = cached
+            .hash
+            .get_or_init(|| to_key(prefix, &k, &mut Vec::new()));
+        let bytes = v
+            .try_to_vec()
+            .unwrap_or_else(|_| crate::panic(ERR_ELEMENT_SERIALIZATION));
+        let evicted = crate::storage_write_evicted(key, &bytes);
+        let _ = cached.value.set(CacheEntry::new_cached(Some(v)));
+        evicted.as_deref().map(Self::deserialize_element)
     }
 
     /// Removes a key from the map, returning the value at the key if the key was previously in the map.
+    ///
+    /// If the key is not cached, the value is removed from the storage immediately, so the removed
+    /// value is returned by the host instead of being read from the storage.
     pub fn remove(&mut self, k: K) -> Option<V>
     where
         K: Clone,
     {
-        self.get_mut_inner(&k).replace(None)
+        let prefix = &self.prefix;
+        let cached = self.cache.get_mut(k.clone());
+        if let Some(entry) = cached.value.get_mut() {
+            return entry.replace(None);
+        }
+        let key = cached
+            .hash
+            .get_or_init(|| to_key(prefix, &k, &mut Vec::new()));
+        let evicted = crate::storage_remove_evicted(key);
+        let _ = cached.value.set(CacheEntry::new_cached(None));
+        evicted.as_deref().map(Self::deserialize_element)
     }
 
     /// Returns true if the map contains a value for the specified key.
@@ -243,8 +295,8 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::super::super::tests::*;
     use super::*;
+    use crate::storage_read;
     use borsh::{BorshDeserialize, BorshSerialize};
 
     #[derive(BorshSerialize, BorshDeserialize, Ord, PartialOrd, Eq, PartialEq, Clone, Debug)]
@@ -439,4 +491,92 @@ mod tests {
         map.remove(1);
         assert!(!map.contains_key(&1));
     }
+
+    #[test]
+    fn test_insert_and_remove_uncached() {
+        let mut map: LookupMap<TestKey, TestValue> = LookupMap::new(b"uncached".to_vec());
+        map.insert(TestKey(1), TestValue(10));
+        map.insert(TestKey(2), TestValue(20));
+        map.flush();
+
+        // The old values are returned from the storage by a new instance of the map
+        let mut map: LookupMap<TestKey, TestValue> = LookupMap::new(b"uncached".to_vec());
+        assert_eq!(map.insert(TestKey(1), TestValue(11)), Some(TestValue(10)));
+        assert_eq!(map.remove(TestKey(2)), Some(TestValue(20)));
+        assert_eq!(map.insert(TestKey(3), TestValue(30)), None);
+        assert_eq!(map.remove(TestKey(4)), None);
+
+        // The changes are written through
+        let stored = |key: &TestKey| {
+            storage_read(&to_key(b"uncached", key, &mut Vec::new()))
+                .map(|bytes| TestValue::try_from_slice(&bytes).unwrap())
+        };
+        assert_eq!(stored(&TestKey(1)), Some(TestValue(11)));
+        assert_eq!(stored(&TestKey(2)), None);
+        assert_eq!(stored(&TestKey(3)), Some(TestValue(30)));
+
+        // The written values are cached
+        assert_eq!(map.get(&TestKey(1)), Some(&TestValue(11)));
+        assert_eq!(map.remove(TestKey(1)), Some(TestValue(11)));
+        map.flush();
+        assert_eq!(stored(&TestKey(1)), None);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map: LookupMap<String, u64> = LookupMap::new(b"entry".to_vec());
+
+        for name in ["a", "b", "a"] {
+            map.entry(name.to_string())
+                .and_modify(|counter| *counter += 1)
+                .or_insert(1);
+        }
+        *map.entry("c".to_string()).or_default() += 5;
+        assert_eq!(map.get("a"), Some(&2));
+        assert_eq!(map.get("b"), Some(&1));
+        assert_eq!(map.get("c"), Some(&5));
+
+        match map.entry("a".to_string()) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.insert(10), 2);
+                assert_eq!(entry.get(), &10);
+            }
+            Entry::Vacant(_) => panic!("The entry is occupied"),
+        }
+        match map.entry("b".to_string()) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), ("b".to_string(), 1)),
+            Entry::Vacant(_) => panic!("The entry is occupied"),
+        }
+        assert!(matches!(map.entry("b".to_string()), Entry::Vacant(_)));
+        assert_eq!(
+            map.entry("d".to_string())
+                .or_insert_with_key(|key| key.len() as u64),
+            &mut 1
+        );
+    }
+
+    #[test]
+    fn test_entry_persistence() {
+        let mut map: LookupMap<TestKey, TestValue> = LookupMap::new(b"entry".to_vec());
+        map.insert(TestKey(1), TestValue(10));
+        map.insert(TestKey(2), TestValue(20));
+        map.flush();
+
+        // The entries are loaded from the storage by a new instance of the map
+        let mut map: LookupMap<TestKey, TestValue> = LookupMap::new(b"entry".to_vec());
+        map.entry(TestKey(1)).and_modify(|value| value.0 += 1);
+        if let Entry::Occupied(entry) = map.entry(TestKey(2)) {
+            assert_eq!(entry.remove(), TestValue(20));
+        }
+        map.entry(TestKey(3)).or_insert_with(|| TestValue(30));
+        map.flush();
+
+        let stored = |key: &TestKey| {
+            storage_read(&to_key(b"entry", key, &mut Vec::new()))
+                .map(|bytes| TestValue::try_from_slice(&bytes).unwrap())
+        };
+        assert_eq!(stored(&TestKey(1)), Some(TestValue(11)));
+        assert_eq!(stored(&TestKey(2)), None);
+        assert_eq!(stored(&TestKey(3)), Some(TestValue(30)));
+    }
 }
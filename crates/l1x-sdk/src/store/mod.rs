@@ -12,5 +12,14 @@ pub use self::lookup_set::LookupSet;
 pub mod lookup_map;
 pub use self::lookup_map::LookupMap;
 
+pub mod unordered_map;
+pub use self::unordered_map::UnorderedMap;
+
+pub mod unordered_set;
+pub use self::unordered_set::UnorderedSet;
+
+pub mod tree_map;
+pub use self::tree_map::TreeMap;
+
 mod index_map;
 pub(crate) use self::index_map::IndexMap;
@@ -0,0 +1,58 @@
+use std::borrow::Borrow;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{Range, TreeMap};
+
+impl<K, V> Drop for TreeMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn drop(&mut self) {
+        self.flush()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for TreeMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, Q: ?Sized> core::ops::Index<&Q> for TreeMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone + Borrow<Q>,
+    V: BorshSerialize + BorshDeserialize,
+
+    Q: BorshSerialize + ToOwned<Owned = K>,
+{
+    type Output = V;
+
+    fn index(&self, index: &Q) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| crate::panic("does not exist"))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a TreeMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Range<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
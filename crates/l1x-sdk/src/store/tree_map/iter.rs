@@ -0,0 +1,115 @@
+use std::iter::FusedIterator;
+use std::ops::Bound;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::TreeMap;
+
+/// An iterator over the key-value pairs of [`TreeMap`] in the order of the keys.
+pub struct Range<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    map: &'a TreeMap<K, V>,
+    /// Nodes that are not visited yet together with their subtrees on the side of the end
+    stack: Vec<u32>,
+    end: Bound<K>,
+    /// Iterates in the descending order
+    rev: bool,
+}
+
+impl<'a, K, V> Range<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    /// Creates an iterator from `start` to `end`. If `rev` is `true`, `start` is the upper bound.
+    pub(super) fn new(map: &'a TreeMap<K, V>, start: Bound<&K>, end: Bound<K>, rev: bool) -> Self {
+        let mut range = Self {
+            map,
+            stack: Vec::new(),
+            end,
+            rev,
+        };
+        let mut next = map.root;
+        while let Some(id) = next {
+            let node = map.node(id);
+            let after_start = match start {
+                Bound::Included(start) => range.ordered(start, &node.key) || *start == node.key,
+                Bound::Excluded(start) => range.ordered(start, &node.key),
+                Bound::Unbounded => true,
+            };
+            if after_start {
+                range.stack.push(id);
+                next = range.toward_start(id);
+            } else {
+                next = range.toward_end(id);
+            }
+        }
+        range
+    }
+
+    /// Returns `true` if `a` comes strictly before `b` in the order of the iteration.
+    fn ordered(&self, a: &K, b: &K) -> bool {
+        if self.rev {
+            a > b
+        } else {
+            a < b
+        }
+    }
+
+    fn toward_start(&self, id: u32) -> Option<u32> {
+        let node = self.map.node(id);
+        if self.rev {
+            node.right
+        } else {
+            node.left
+        }
+    }
+
+    fn toward_end(&self, id: u32) -> Option<u32> {
+        let node = self.map.node(id);
+        if self.rev {
+            node.left
+        } else {
+            node.right
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+        let key = &self.map.node(id).key;
+        let before_end = match &self.end {
+            Bound::Included(end) => self.ordered(key, end) || key == end,
+            Bound::Excluded(end) => self.ordered(key, end),
+            Bound::Unbounded => true,
+        };
+        if !before_end {
+            self.stack.clear();
+            return None;
+        }
+        let mut next = self.toward_end(id);
+        while let Some(id) = next {
+            self.stack.push(id);
+            next = self.toward_start(id);
+        }
+        let value = self.map.get(key).unwrap_or_else(|| crate::abort());
+        Some((key, value))
+    }
+}
+
+impl<'a, K, V> FusedIterator for Range<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+}
@@ -0,0 +1,507 @@
+//! An ordered implementation of a map that stores its content directly on the persistent storage.
+mod impls;
+mod iter;
+
+pub use self::iter::Range;
+
+use super::{IndexMap, LookupMap};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+/// An ordered implementation of a map that stores its content directly on the persistent storage.
+///
+/// Keys are kept in an AVL tree whose nodes are stored by their ids, values are kept in a
+/// [`LookupMap`]. Lookups, insertions and removals load O(log n) nodes.
+///
+/// All operations are cached. The cache is flushed in the following cases:
+///
+/// * [`Self::flush`] method is called
+/// * [`drop`] method is called
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct TreeMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    root: Option<u32>,
+    len: u32,
+    /// Nodes by their ids. Ids are in `0..len`.
+    nodes: IndexMap<Node<K>>,
+    values: LookupMap<K, V>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+struct Node<K> {
+    key: K,
+    left: Option<u32>,
+    right: Option<u32>,
+    height: u32,
+}
+
+impl<K, V> TreeMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    /// Creates a new map. Uses `prefix` as a unique prefix for keys.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            nodes: IndexMap::new([&prefix[..], b"n"].concat()),
+            values: LookupMap::new([&prefix[..], b"v"].concat()),
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// If the map doesn't have the key present, returns `None`
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.get(k)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// If the map doesn't have the key present, returns `None`
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.get_mut(k)
+    }
+
+    /// Returns true if the map contains a value for the specified key.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.contains_key(k)
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, None is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old value is returned.
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        if let Some(value) = self.values.get_mut(&k) {
+            return Some(std::mem::replace(value, v));
+        }
+        let id = self.len;
+        self.nodes.set(
+            id,
+            Some(Node {
+                key: k.clone(),
+                left: None,
+                right: None,
+                height: 1,
+            }),
+        );
+        self.len += 1;
+        self.root = Some(self.insert_at(self.root, id));
+        self.values.set(k, Some(v));
+        None
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in the map.
+    pub fn remove(&mut self, k: K) -> Option<V> {
+        let value = self.values.remove(k.clone())?;
+        let (root, removed) = self.remove_at(self.root, &k);
+        self.root = root;
+        self.release(removed.unwrap_or_else(|| crate::abort()));
+        Some(value)
+    }
+
+    /// Returns the smallest key of the map.
+    pub fn min(&self) -> Option<&K> {
+        let mut id = self.root?;
+        while let Some(left) = self.node(id).left {
+            id = left;
+        }
+        Some(&self.node(id).key)
+    }
+
+    /// Returns the largest key of the map.
+    pub fn max(&self) -> Option<&K> {
+        let mut id = self.root?;
+        while let Some(right) = self.node(id).right {
+            id = right;
+        }
+        Some(&self.node(id).key)
+    }
+
+    /// Returns the largest key less than or equal to `key`.
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let mut found = None;
+        let mut next = self.root;
+        while let Some(id) = next {
+            let node = self.node(id);
+            if node.key == *key {
+                return Some(&node.key);
+            }
+            if node.key < *key {
+                found = Some(&node.key);
+                next = node.right;
+            } else {
+                next = node.left;
+            }
+        }
+        found
+    }
+
+    /// Returns the smallest key greater than or equal to `key`.
+    pub fn ceil(&self, key: &K) -> Option<&K> {
+        let mut found = None;
+        let mut next = self.root;
+        while let Some(id) = next {
+            let node = self.node(id);
+            if node.key == *key {
+                return Some(&node.key);
+            }
+            if node.key > *key {
+                found = Some(&node.key);
+                next = node.left;
+            } else {
+                next = node.right;
+            }
+        }
+        found
+    }
+
+    /// Returns an iterator over the key-value pairs in the ascending order of the keys.
+    pub fn iter(&self) -> Range<K, V> {
+        Range::new(self, Bound::Unbounded, Bound::Unbounded, false)
+    }
+
+    /// Returns an iterator over the key-value pairs in the descending order of the keys.
+    pub fn iter_rev(&self) -> Range<K, V> {
+        Range::new(self, Bound::Unbounded, Bound::Unbounded, true)
+    }
+
+    /// Returns an iterator over the key-value pairs with the keys in `range` in the ascending
+    /// order of the keys. Only the nodes on the way to the returned elements are loaded.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<K, V> {
+        Range::new(self, range.start_bound(), range.end_bound().cloned(), false)
+    }
+
+    /// Writes the cached operations to the persistent storage.
+    ///
+    /// # Panic
+    ///
+    /// Panics if serialization fails
+    pub fn flush(&mut self) {
+        self.nodes.flush();
+        self.values.flush();
+    }
+
+    fn node(&self, id: u32) -> &Node<K> {
+        self.nodes.get(id).unwrap_or_else(|| crate::abort())
+    }
+
+    fn node_mut(&mut self, id: u32) -> &mut Node<K> {
+        self.nodes.get_mut(id).unwrap_or_else(|| crate::abort())
+    }
+
+    fn height(&self, id: Option<u32>) -> u32 {
+        id.map_or(0, |id| self.node(id).height)
+    }
+
+    fn balance_factor(&self, id: u32) -> i64 {
+        let node = self.node(id);
+        i64::from(self.height(node.left)) - i64::from(self.height(node.right))
+    }
+
+    fn update_height(&mut self, id: u32) {
+        let node = self.node(id);
+        let height = 1 + self.height(node.left).max(self.height(node.right));
+        if node.height != height {
+            self.node_mut(id).height = height;
+        }
+    }
+
+    fn rotate_right(&mut self, id: u32) -> u32 {
+        let left = self.node(id).left.unwrap_or_else(|| crate::abort());
+        self.node_mut(id).left = self.node(left).right;
+        self.node_mut(left).right = Some(id);
+        self.update_height(id);
+        self.update_height(left);
+        left
+    }
+
+    fn rotate_left(&mut self, id: u32) -> u32 {
+        let right = self.node(id).right.unwrap_or_else(|| crate::abort());
+        self.node_mut(id).right = self.node(right).left;
+        self.node_mut(right).left = Some(id);
+        self.update_height(id);
+        self.update_height(right);
+        right
+    }
+
+    /// Restores the balance of the subtree at `id`. Returns the new root of the subtree.
+    fn rebalance(&mut self, id: u32) -> u32 {
+        self.update_height(id);
+        let balance_factor = self.balance_factor(id);
+        if balance_factor > 1 {
+            let left = self.node(id).left.unwrap_or_else(|| crate::abort());
+            if self.balance_factor(left) < 0 {
+                self.node_mut(id).left = Some(self.rotate_left(left));
+            }
+            self.rotate_right(id)
+        } else if balance_factor < -1 {
+            let right = self.node(id).right.unwrap_or_else(|| crate::abort());
+            if self.balance_factor(right) > 0 {
+                self.node_mut(id).right = Some(self.rotate_right(right));
+            }
+            self.rotate_left(id)
+        } else {
+            id
+        }
+    }
+
+    /// Inserts the node `new` into the subtree at `at`. Returns the new root of the subtree.
+    fn insert_at(&mut self, at: Option<u32>, new: u32) -> u32 {
+        let Some(id) = at else {
+            return new;
+        };
+        if self.node(new).key < self.node(id).key {
+            let left = self.insert_at(self.node(id).left, new);
+            self.node_mut(id).left = Some(left);
+        } else {
+            let right = self.insert_at(self.node(id).right, new);
+            self.node_mut(id).right = Some(right);
+        }
+        self.rebalance(id)
+    }
+
+    /// Detaches the node with `key` from the subtree at `at`. Returns the new root of the subtree
+    /// and the id of the detached node.
+    fn remove_at(&mut self, at: Option<u32>, key: &K) -> (Option<u32>, Option<u32>) {
+        let Some(id) = at else {
+            return (None, None);
+        };
+        let node = self.node(id);
+        let removed = match key.cmp(&node.key) {
+            Ordering::Less => {
+                let (left, removed) = self.remove_at(node.left, key);
+                self.node_mut(id).left = left;
+                removed
+            }
+            Ordering::Greater => {
+                let (right, removed) = self.remove_at(node.right, key);
+                self.node_mut(id).right = right;
+                removed
+            }
+            Ordering::Equal => {
+                let (left, right) = (node.left, node.right);
+                let Some(right) = right else {
+                    return (left, Some(id));
+                };
+                if left.is_none() {
+                    return (Some(right), Some(id));
+                }
+                // The smallest node of the right subtree takes the place of the removed one
+                let (right, min) = self.remove_min(right);
+                let min_node = self.node_mut(min);
+                min_node.left = left;
+                min_node.right = right;
+                return (Some(self.rebalance(min)), Some(id));
+            }
+        };
+        (Some(self.rebalance(id)), removed)
+    }
+
+    /// Detaches the smallest node of the subtree at `id`. Returns the new root of the subtree and
+    /// the id of the detached node.
+    fn remove_min(&mut self, id: u32) -> (Option<u32>, u32) {
+        match self.node(id).left {
+            None => (self.node(id).right, id),
+            Some(left) => {
+                let (left, min) = self.remove_min(left);
+                self.node_mut(id).left = left;
+                (Some(self.rebalance(id)), min)
+            }
+        }
+    }
+
+    /// Frees the id of the detached node by moving the last node to it.
+    fn release(&mut self, id: u32) {
+        self.len -= 1;
+        let last = self.len;
+        if id != last {
+            let node = self.node(last).clone();
+            if self.root == Some(last) {
+                self.root = Some(id);
+            } else {
+                let mut parent = self.root.unwrap_or_else(|| crate::abort());
+                loop {
+                    let parent_node = self.node(parent);
+                    let child = if node.key < parent_node.key {
+                        parent_node.left
+                    } else {
+                        parent_node.right
+                    };
+                    match child {
+                        Some(child) if child == last => break,
+                        Some(child) => parent = child,
+                        None => crate::abort(),
+                    }
+                }
+                let parent_node = self.node_mut(parent);
+                if parent_node.left == Some(last) {
+                    parent_node.left = Some(id);
+                } else {
+                    parent_node.right = Some(id);
+                }
+            }
+            self.nodes.set(id, Some(node));
+        }
+        self.nodes.set(last, None);
+    }
+}
+
+//====================================================== TESTS =================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the order and the balance of the tree. Returns the height of the subtree.
+    fn check_subtree(map: &TreeMap<u32, u32>, id: Option<u32>, keys: &mut Vec<u32>) -> u32 {
+        let Some(id) = id else {
+            return 0;
+        };
+        let node = map.node(id).clone();
+        let left = check_subtree(map, node.left, keys);
+        keys.push(node.key);
+        let right = check_subtree(map, node.right, keys);
+        assert!(left.abs_diff(right) <= 1, "The tree is not balanced");
+        assert_eq!(node.height, 1 + left.max(right));
+        node.height
+    }
+
+    fn check_tree(map: &TreeMap<u32, u32>) -> Vec<u32> {
+        let mut keys = Vec::new();
+        check_subtree(map, map.root, &mut keys);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(keys.len() as u32, map.len());
+        keys
+    }
+
+    /// Keys in a scrambled order.
+    fn scrambled(count: u32) -> impl Iterator<Item = u32> {
+        (0..count).map(move |i| (i * 37) % count)
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map: TreeMap<u32, u32> = TreeMap::new(b"test".to_vec());
+        assert!(map.is_empty());
+
+        map.extend(scrambled(100).map(|k| (k, k * 10)));
+        assert_eq!(check_tree(&map), (0..100).collect::<Vec<_>>());
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.get(&42), Some(&420));
+        assert_eq!(map.get(&100), None);
+        assert!(map.contains_key(&99));
+
+        assert_eq!(map.insert(42, 1), Some(420));
+        *map.get_mut(&43).unwrap() = 2;
+        assert_eq!((map[&42], map[&43]), (1, 2));
+        assert_eq!(map.len(), 100);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map: TreeMap<u32, u32> = TreeMap::new(b"test".to_vec());
+        map.extend(scrambled(64).map(|k| (k, k)));
+
+        for key in scrambled(64).filter(|k| k % 3 != 0) {
+            assert_eq!(map.remove(key), Some(key));
+            check_tree(&map);
+        }
+        assert_eq!(map.remove(1), None);
+        assert_eq!(
+            check_tree(&map),
+            (0..64).filter(|k| k % 3 == 0).collect::<Vec<_>>()
+        );
+
+        for key in (0..64).filter(|k| k % 3 == 0) {
+            assert_eq!(map.remove(key), Some(key));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.min(), None);
+    }
+
+    #[test]
+    fn test_min_max_floor_ceil() {
+        let mut map: TreeMap<u32, u32> = TreeMap::new(b"test".to_vec());
+        assert_eq!((map.min(), map.max()), (None, None));
+        assert_eq!((map.floor(&1), map.ceil(&1)), (None, None));
+
+        map.extend([10, 30, 20, 50, 40].map(|k| (k, k)));
+        assert_eq!((map.min(), map.max()), (Some(&10), Some(&50)));
+        assert_eq!(map.floor(&30), Some(&30));
+        assert_eq!(map.floor(&35), Some(&30));
+        assert_eq!(map.floor(&9), None);
+        assert_eq!(map.ceil(&30), Some(&30));
+        assert_eq!(map.ceil(&35), Some(&40));
+        assert_eq!(map.ceil(&51), None);
+    }
+
+    #[test]
+    fn test_iter_and_range() {
+        let mut map: TreeMap<u32, u32> = TreeMap::new(b"test".to_vec());
+        map.extend(scrambled(20).map(|k| (k * 2, k)));
+
+        let keys = |iter: Range<u32, u32>| iter.map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!(keys(map.iter()), (0..20).map(|k| k * 2).collect::<Vec<_>>());
+        assert_eq!(
+            keys(map.iter_rev()),
+            (0..20).rev().map(|k| k * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(map.iter().next(), Some((&0, &0)));
+        assert_eq!((&map).into_iter().count(), 20);
+
+        assert_eq!(keys(map.range(10..16)), vec![10, 12, 14]);
+        assert_eq!(keys(map.range(9..=16)), vec![10, 12, 14, 16]);
+        assert_eq!(keys(map.range(..4)), vec![0, 2]);
+        assert_eq!(keys(map.range(35..)), vec![36, 38]);
+        assert_eq!(
+            keys(map.range((Bound::Excluded(10), Bound::Excluded(14)))),
+            vec![12]
+        );
+        assert!(keys(map.range(100..)).is_empty());
+        assert!(keys(map.range(11..12)).is_empty());
+    }
+
+    #[test]
+    fn test_persistence() {
+        let mut map: TreeMap<u32, u32> = TreeMap::new(b"persistent".to_vec());
+        map.extend(scrambled(10).map(|k| (k, k * 10)));
+        map.remove(5);
+        let state = map.try_to_vec().unwrap();
+        drop(map);
+
+        let map = TreeMap::<u32, u32>::try_from_slice(&state).unwrap();
+        assert_eq!(check_tree(&map), vec![0, 1, 2, 3, 4, 6, 7, 8, 9]);
+        assert_eq!(map.get(&9), Some(&90));
+    }
+}
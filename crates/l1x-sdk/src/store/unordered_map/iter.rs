@@ -0,0 +1,206 @@
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::UnorderedMap;
+use crate::store::{vec, Vector};
+
+/// An iterator over the key-value pairs of [`UnorderedMap`].
+pub struct Iter<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord,
+    V: BorshSerialize + BorshDeserialize,
+{
+    map: &'a UnorderedMap<K, V>,
+    range: Range<u32>,
+}
+
+impl<'a, K, V> Iter<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    pub(super) fn new(map: &'a UnorderedMap<K, V>) -> Self {
+        Self {
+            map,
+            range: 0..map.len(),
+        }
+    }
+
+    fn entry(&self, index: u32) -> (&'a K, &'a V) {
+        let key = self.map.keys.get(index).unwrap_or_else(|| crate::abort());
+        let value = self.map.get(key).unwrap_or_else(|| crate::abort());
+        (key, value)
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.entry(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).map(|index| self.entry(index))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|index| self.entry(index))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth_back(n).map(|index| self.entry(index))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+}
+
+/// An iterator over the keys of [`UnorderedMap`].
+pub struct Keys<'a, K>
+where
+    K: BorshSerialize + BorshDeserialize,
+{
+    inner: vec::Iter<'a, K>,
+}
+
+impl<'a, K> Keys<'a, K>
+where
+    K: BorshSerialize + BorshDeserialize,
+{
+    pub(super) fn new(keys: &'a Vector<K>) -> Self {
+        Self { inner: keys.iter() }
+    }
+}
+
+impl<'a, K> Iterator for Keys<'a, K>
+where
+    K: BorshSerialize + BorshDeserialize,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n)
+    }
+}
+
+impl<'a, K> DoubleEndedIterator for Keys<'a, K>
+where
+    K: BorshSerialize + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth_back(n)
+    }
+}
+
+impl<'a, K> ExactSizeIterator for Keys<'a, K> where K: BorshSerialize + BorshDeserialize {}
+
+impl<'a, K> FusedIterator for Keys<'a, K> where K: BorshSerialize + BorshDeserialize {}
+
+/// An iterator over the values of [`UnorderedMap`].
+pub struct Values<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord,
+    V: BorshSerialize + BorshDeserialize,
+{
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Values<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    pub(super) fn new(map: &'a UnorderedMap<K, V>) -> Self {
+        Self {
+            inner: Iter::new(map),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n).map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth_back(n).map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+}
+
+impl<'a, K, V> FusedIterator for Values<'a, K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord + Clone,
+    V: BorshSerialize + BorshDeserialize,
+{
+}
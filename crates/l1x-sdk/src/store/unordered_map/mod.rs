@@ -0,0 +1,244 @@
+//! An iterable implementation of a map that stores its content directly on the persistent storage.
+mod impls;
+mod iter;
+
+pub use self::iter::{Iter, Keys, Values};
+
+use super::{LookupMap, Vector};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::borrow::Borrow;
+
+/// An iterable implementation of a map that stores its content directly on the persistent storage.
+///
+/// Keys are kept in a [`Vector`] in the insertion order, values are kept in a [`LookupMap`]
+/// together with the index of their key. Removal swaps the removed key with the last one, so it
+/// doesn't preserve the order but is O(1).
+///
+/// All operations are cached. The cache is flushed in the following cases:
+///
+/// * [`Self::flush`] method is called
+/// * [`drop`] method is called
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord,
+    V: BorshSerialize + BorshDeserialize,
+{
+    keys: Vector<K>,
+    values: LookupMap<K, ValueAndIndex<V>>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct ValueAndIndex<V> {
+    value: V,
+    /// The index of the key in `keys`
+    index: u32,
+}
+
+impl<K, V> UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize + Ord,
+    V: BorshSerialize + BorshDeserialize,
+{
+    /// Creates a new map. Uses `prefix` as a unique prefix for keys.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            keys: Vector::new([&prefix[..], b"k"].concat()),
+            values: LookupMap::new([&prefix[..], b"v"].concat()),
+        }
+    }
+
+    /// Returns the number of elements in the map.
+    pub fn len(&self) -> u32 {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// If the map doesn't have the key present, returns `None`
+    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.get(k).map(|entry| &entry.value)
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// If the map doesn't have the key present, returns `None`
+    pub fn get_mut<Q: ?Sized>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.get_mut(k).map(|entry| &mut entry.value)
+    }
+
+    /// Returns true if the map contains a value for the specified key.
+    pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = K>,
+    {
+        self.values.contains_key(k)
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, None is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old value is returned.
+    pub fn insert(&mut self, k: K, v: V) -> Option<V>
+    where
+        K: Clone,
+    {
+        if let Some(entry) = self.values.get_mut(&k) {
+            return Some(std::mem::replace(&mut entry.value, v));
+        }
+        let index = self.keys.len();
+        self.keys.push(k.clone());
+        self.values.set(k, Some(ValueAndIndex { value: v, index }));
+        None
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in the map.
+    ///
+    /// The last key takes the place of the removed one.
+    pub fn remove(&mut self, k: K) -> Option<V>
+    where
+        K: Clone,
+    {
+        let ValueAndIndex { value, index } = self.values.remove(k)?;
+        self.keys.swap_remove(index);
+        if let Some(moved) = self.keys.get(index).cloned() {
+            self.values
+                .get_mut(&moved)
+                .unwrap_or_else(|| crate::abort())
+                .index = index;
+        }
+        Some(value)
+    }
+
+    /// Returns an iterator over the key-value pairs in the order of the keys.
+    ///
+    /// The iterator supports pagination with [`Iterator::skip`] and [`Iterator::take`], only the
+    /// returned elements are loaded from the storage.
+    pub fn iter(&self) -> Iter<K, V>
+    where
+        K: Clone,
+    {
+        Iter::new(self)
+    }
+
+    /// Returns an iterator over the keys. The values are not loaded.
+    pub fn keys(&self) -> Keys<K> {
+        Keys::new(&self.keys)
+    }
+
+    /// Returns an iterator over the values in the order of the keys.
+    pub fn values(&self) -> Values<K, V>
+    where
+        K: Clone,
+    {
+        Values::new(self)
+    }
+
+    /// Writes the cached operations to the persistent storage.
+    ///
+    /// # Panic
+    ///
+    /// Panics if serialization fails
+    pub fn flush(&mut self) {
+        self.keys.flush();
+        self.values.flush();
+    }
+}
+
+//====================================================== TESTS =================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map: UnorderedMap<String, u64> = UnorderedMap::new(b"test".to_vec());
+        assert!(map.is_empty());
+
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("b".to_string(), 2), None);
+        assert_eq!(map.insert("a".to_string(), 3), Some(1));
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("a"), Some(&3));
+        assert_eq!(map.get("b"), Some(&2));
+        assert_eq!(map.get("c"), None);
+        assert!(map.contains_key("b"));
+
+        *map.get_mut("b").unwrap() = 4;
+        assert_eq!(map["b"], 4);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map: UnorderedMap<u32, u32> = UnorderedMap::new(b"test".to_vec());
+        map.extend((0..4).map(|i| (i, i * 10)));
+
+        assert_eq!(map.remove(1), Some(10));
+        assert_eq!(map.remove(1), None);
+        assert_eq!(map.len(), 3);
+        // The last key took the place of the removed one
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![0, 3, 2]);
+
+        // The index of the moved key is updated
+        assert_eq!(map.remove(3), Some(30));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(map.remove(2), Some(20));
+        assert_eq!(map.remove(0), Some(0));
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut map: UnorderedMap<u32, String> = UnorderedMap::new(b"test".to_vec());
+        map.extend((0..10).map(|i| (i, i.to_string())));
+
+        let pairs: Vec<_> = map.iter().map(|(k, v)| (*k, v.clone())).collect();
+        assert_eq!(
+            pairs,
+            (0..10).map(|i| (i, i.to_string())).collect::<Vec<_>>()
+        );
+        assert_eq!(map.values().last(), Some(&"9".to_string()));
+        assert_eq!(map.iter().len(), 10);
+        assert_eq!(map.iter().next_back(), Some((&9, &"9".to_string())));
+        assert_eq!((&map).into_iter().count(), 10);
+
+        // Pagination
+        let page: Vec<_> = map.iter().skip(4).take(3).map(|(k, _)| *k).collect();
+        assert_eq!(page, vec![4, 5, 6]);
+        assert_eq!(map.keys().skip(8).take(5).count(), 2);
+    }
+
+    #[test]
+    fn test_persistence() {
+        let mut map: UnorderedMap<u32, u32> = UnorderedMap::new(b"persistent".to_vec());
+        map.extend((0..3).map(|i| (i, i * 10)));
+        map.remove(0);
+        let state = map.try_to_vec().unwrap();
+        drop(map);
+
+        let map = UnorderedMap::<u32, u32>::try_from_slice(&state).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+            vec![(2, 20), (1, 10)]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{Iter, UnorderedSet};
+
+impl<T> Drop for UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    fn drop(&mut self) {
+        self.flush()
+    }
+}
+
+impl<T> Extend<T> for UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord + Clone,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter().for_each(move |elem| {
+            self.insert(elem);
+        });
+    }
+}
+
+impl<'a, T> IntoIterator for &'a UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,149 @@
+//! An iterable implementation of a set that stores its content directly on the persistent storage.
+mod impls;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::borrow::Borrow;
+
+use crate::store::UnorderedMap;
+
+/// An iterator over the elements of [`UnorderedSet`].
+pub type Iter<'a, T> = crate::store::unordered_map::Keys<'a, T>;
+
+/// An iterable implementation of a set that stores its content directly on the persistent storage.
+/// UnorderedSet is essentially an UnorderedMap where the key is the element
+/// and the value is a constant to signify its presence.
+///
+/// Elements are kept in a [`crate::store::Vector`] in the insertion order. Removal swaps the removed
+/// element with the last one, so it doesn't preserve the order but is O(1).
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    map: UnorderedMap<T, ()>,
+}
+
+impl<T> UnorderedSet<T>
+where
+    T: BorshSerialize + BorshDeserialize + Ord,
+{
+    /// Creates a new set. Uses `prefix` as a unique prefix for keys.
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            map: UnorderedMap::new(prefix),
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> u32 {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns whether the value was newly inserted. That is:
+    ///
+    /// * If the set did not previously contain this value, true is returned.
+    /// * If the set already contained this value, false is returned.
+    pub fn insert(&mut self, value: T) -> bool
+    where
+        T: Clone,
+    {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Removes a value from the set. Returns whether the value was present in the set.
+    pub fn remove(&mut self, value: T) -> bool
+    where
+        T: Clone,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Returns true if the set contains a value.
+    pub fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: BorshSerialize + ToOwned<Owned = T>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns an iterator over the elements in the order of insertion.
+    ///
+    /// The iterator supports pagination with [`Iterator::skip`] and [`Iterator::take`], only the
+    /// returned elements are loaded from the storage.
+    pub fn iter(&self) -> Iter<T> {
+        self.map.keys()
+    }
+
+    /// Flushes the set's cache.
+    pub fn flush(&mut self) {
+        self.map.flush();
+    }
+}
+
+//======================================================= TESTS =======================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut set: UnorderedSet<String> = UnorderedSet::new(b"test".to_vec());
+        assert!(set.is_empty());
+
+        assert!(set.insert("a".to_string()));
+        assert!(set.insert("b".to_string()));
+        // Inserting the same value again should return false
+        assert!(!set.insert("a".to_string()));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("a"));
+        assert!(!set.contains("c"));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: UnorderedSet<u32> = UnorderedSet::new(b"test".to_vec());
+        set.extend([10, 20, 30]);
+
+        assert!(set.remove(10));
+        assert!(!set.remove(10));
+        assert!(!set.contains(&10));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![30, 20]);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut set: UnorderedSet<u32> = UnorderedSet::new(b"test".to_vec());
+        set.extend(0..10);
+
+        assert_eq!(set.iter().len(), 10);
+        assert_eq!((&set).into_iter().copied().sum::<u32>(), 45);
+
+        // Pagination
+        let page: Vec<_> = set.iter().skip(4).take(3).copied().collect();
+        assert_eq!(page, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_persistence() {
+        let mut set: UnorderedSet<u32> = UnorderedSet::new(b"persistent".to_vec());
+        set.extend([1, 2, 3]);
+        let state = set.try_to_vec().unwrap();
+        drop(set);
+
+        let set = UnorderedSet::<u32>::try_from_slice(&state).unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&2));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+}
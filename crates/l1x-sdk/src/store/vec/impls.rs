@@ -1,6 +1,6 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use super::{Vector, ERR_INDEX_OUT_OF_BOUNDS};
+use super::{Iter, IterMut, Vector, ERR_INDEX_OUT_OF_BOUNDS};
 
 impl<T> Drop for Vector<T>
 where
@@ -36,3 +36,27 @@ where
             .unwrap_or_else(|| crate::panic(ERR_INDEX_OUT_OF_BOUNDS))
     }
 }
+
+impl<'a, T> IntoIterator for &'a Vector<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Vector<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
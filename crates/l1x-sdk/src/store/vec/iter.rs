@@ -0,0 +1,176 @@
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::Vector;
+
+/// An iterator over references to the elements of [`Vector`].
+pub struct Iter<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    vec: &'a Vector<T>,
+    range: Range<u32>,
+}
+
+impl<'a, T> Iter<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    pub(super) fn new(vec: &'a Vector<T>) -> Self {
+        Self {
+            vec,
+            range: 0..vec.len(),
+        }
+    }
+
+    fn element(&self, index: u32) -> &'a T {
+        self.vec.get(index).unwrap_or_else(|| crate::abort())
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.element(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).map(|index| self.element(index))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|index| self.element(index))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth_back(n).map(|index| self.element(index))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> where T: BorshSerialize + BorshDeserialize {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> where T: BorshSerialize + BorshDeserialize {}
+
+/// An iterator over mutable references to the elements of [`Vector`].
+pub struct IterMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    vec: *mut Vector<T>,
+    range: Range<u32>,
+    _marker: PhantomData<&'a mut Vector<T>>,
+}
+
+impl<'a, T> IterMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    pub(super) fn new(vec: &'a mut Vector<T>) -> Self {
+        Self {
+            range: 0..vec.len(),
+            vec,
+            _marker: PhantomData,
+        }
+    }
+
+    fn element(&mut self, index: u32) -> &'a mut T {
+        // The cached elements are boxed, so the references stay valid while new elements are
+        // loaded. Each index is yielded once, so the references don't alias.
+        let vec = unsafe { &mut *self.vec };
+        vec.get_mut(index).unwrap_or_else(|| crate::abort())
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(self.element(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.range.nth(n)?;
+        Some(self.element(index))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(self.element(index))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.range.nth_back(n)?;
+        Some(self.element(index))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> where T: BorshSerialize + BorshDeserialize {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> where T: BorshSerialize + BorshDeserialize {}
+
+/// An iterator over the elements removed by [`Vector::drain`].
+///
+/// The elements are removed from the vector when the iterator is created, dropping the iterator
+/// without consuming it doesn't return them.
+pub struct Drain<T> {
+    elements: std::vec::IntoIter<T>,
+}
+
+impl<T> Drain<T> {
+    pub(super) fn new(elements: Vec<T>) -> Self {
+        Self {
+            elements: elements.into_iter(),
+        }
+    }
+}
+
+impl<T> Iterator for Drain<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.elements.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.elements.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.elements.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<T> {}
+
+impl<T> FusedIterator for Drain<T> {}
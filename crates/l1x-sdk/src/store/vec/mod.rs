@@ -1,5 +1,10 @@
 //! An iterable implementation of vector that stores its content to the persitent storage.
 mod impls;
+mod iter;
+
+pub use self::iter::{Drain, Iter, IterMut};
+
+use std::ops::{Bound, RangeBounds};
 
 use crate::abort;
 
@@ -126,12 +131,7 @@ where
         }
         self.values.get_mut(index)
     }
-}
 
-impl<T> Vector<T>
-where
-    T: BorshSerialize + BorshDeserialize + Copy,
-{
     /// Removes the last element from a vector and returns it, or `None` if it is empty.
     pub fn pop(&mut self) -> Option<T> {
         if self.len() == 0 {
@@ -139,9 +139,7 @@ where
         }
 
         let last_idx = self.len() - 1;
-        let last_value = self.values.get(last_idx).copied();
-
-        self.values.set(last_idx, None);
+        let last_value = self.values.get_mut_inner(last_idx).replace(None);
 
         self.len -= 1;
 
@@ -152,7 +150,7 @@ where
     ///
     /// The removed element is replaced by the last element of the vector.
     ///
-    /// This does not preserve ordering, but is O(1). If you need to preserve the element order, use remove instead.
+    /// This does not preserve ordering, but is O(1). If you need to preserve the element order, use [`Self::remove`] instead.
     ///
     /// # Panics
     ///
@@ -166,21 +164,173 @@ where
         if last_idx == index {
             self.pop().unwrap_or_else(|| abort())
         } else {
-            let elem = self.values.get(index).copied().unwrap_or_else(|| abort());
             let last_elem = self.pop();
 
-            self.values.set(index, last_elem);
-            elem
+            self.values
+                .get_mut_inner(index)
+                .replace(last_elem)
+                .unwrap_or_else(|| abort())
         }
     }
+
+    /// Inserts an element at `index`, shifting all elements after it to the right.
+    ///
+    /// Every shifted element is loaded and rewritten, so this is O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len` or if the new length exceeds [`u32::MAX`].
+    pub fn insert(&mut self, index: u32, element: T) {
+        if index > self.len() {
+            crate::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+        self.len = self
+            .len
+            .checked_add(1)
+            .unwrap_or_else(|| crate::panic(ERR_INDEX_OUT_OF_BOUNDS));
+
+        let mut carry = Some(element);
+        for i in index..self.len() {
+            carry = self.values.get_mut_inner(i).replace(carry);
+        }
+    }
+
+    /// Removes the element at `index` and returns it, shifting all elements after it to the left.
+    ///
+    /// This preserves the element order, but every shifted element is loaded and rewritten, so
+    /// this is O(n). If you don't need to preserve the order, use [`Self::swap_remove`] instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: u32) -> T {
+        if index >= self.len() {
+            crate::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+
+        let mut carry = None;
+        for i in (index..self.len()).rev() {
+            carry = self.values.get_mut_inner(i).replace(carry);
+        }
+        self.len -= 1;
+
+        carry.unwrap_or_else(|| abort())
+    }
+
+    /// Swaps two elements in the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    pub fn swap(&mut self, a: u32, b: u32) {
+        if a >= self.len() || b >= self.len() {
+            crate::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+        if a == b {
+            return;
+        }
+
+        let a_value = self.values.get_mut_inner(a).replace(None);
+        let b_value = self.values.get_mut_inner(b).replace(a_value);
+        self.values.get_mut_inner(a).replace(b_value);
+    }
+
+    /// Returns the first element of the vector, or `None` if it is empty.
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the last element of the vector, or `None` if it is empty.
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Shortens the vector, keeping the first `len` elements. The storage keys of the removed
+    /// elements are deleted on [`Self::flush`].
+    ///
+    /// Has no effect if `len` is greater than or equal to the current length.
+    pub fn truncate(&mut self, len: u32) {
+        for i in len..self.len() {
+            self.values.set(i, None);
+        }
+        self.len = self.len().min(len);
+    }
+
+    /// Removes all elements from the vector. The storage keys of the elements are deleted on
+    /// [`Self::flush`].
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Removes the elements in `range` from the vector and returns them as an iterator, shifting
+    /// the elements after the range to the left.
+    ///
+    /// Unlike [`Vec::drain`], the elements are removed immediately, even if the iterator is not
+    /// consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end or if the end of the range is
+    /// greater than the length of the vector.
+    pub fn drain<R>(&mut self, range: R) -> Drain<T>
+    where
+        R: RangeBounds<u32>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start
+                .checked_add(1)
+                .unwrap_or_else(|| crate::panic(ERR_INDEX_OUT_OF_BOUNDS)),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end
+                .checked_add(1)
+                .unwrap_or_else(|| crate::panic(ERR_INDEX_OUT_OF_BOUNDS)),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        if start > end || end > self.len() {
+            crate::panic(ERR_INDEX_OUT_OF_BOUNDS);
+        }
+
+        let drained = (start..end)
+            .map(|i| {
+                self.values
+                    .get_mut_inner(i)
+                    .replace(None)
+                    .unwrap_or_else(|| abort())
+            })
+            .collect();
+
+        let count = end - start;
+        for i in end..self.len() {
+            let value = self.values.get_mut_inner(i).replace(None);
+            self.values.get_mut_inner(i - count).replace(value);
+        }
+        self.len -= count;
+
+        Drain::new(drained)
+    }
+
+    /// Returns an iterator over the elements. The elements are loaded when they are reached.
+    pub fn iter(&self) -> Iter<T> {
+        Iter::new(self)
+    }
+
+    /// Returns an iterator that allows modifying each element. The elements are loaded when they
+    /// are reached.
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        IterMut::new(self)
+    }
 }
 
 //====================================================== TESTS =================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::super::super::tests::*;
     use super::*;
+    use crate::storage_read;
     use borsh::{BorshDeserialize, BorshSerialize};
 
     #[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug)]
@@ -281,6 +431,17 @@ mod tests {
         assert_eq!(vector.get(1), Some(&TestValue(50)));
     }
 
+    #[test]
+    fn test_vector_swap_remove_non_copy() {
+        let mut vector: Vector<String> = Vector::new(b"test".to_vec());
+        vector.extend(["a", "b", "c"].map(String::from));
+
+        assert_eq!(vector.swap_remove(0), "a");
+        assert_eq!(vector.get(0), Some(&"c".to_string()));
+        assert_eq!(vector.pop(), Some("b".to_string()));
+        assert_eq!(vector.len(), 1);
+    }
+
     #[test]
     #[should_panic]
     fn test_vector_swap_remove_panic() {
@@ -290,6 +451,131 @@ mod tests {
         vector.swap_remove(1);
     }
 
+    #[test]
+    fn test_vector_insert_and_remove() {
+        let mut vector: Vector<String> = Vector::new(b"test".to_vec());
+        vector.extend(["a", "c"].map(String::from));
+
+        vector.insert(1, "b".to_string());
+        vector.insert(0, "_".to_string());
+        vector.insert(4, "d".to_string());
+        assert_eq!(vector.iter().collect::<Vec<_>>(), ["_", "a", "b", "c", "d"]);
+
+        assert_eq!(vector.remove(0), "_");
+        assert_eq!(vector.remove(3), "d");
+        assert_eq!(vector.remove(1), "b");
+        assert_eq!(vector.iter().collect::<Vec<_>>(), ["a", "c"]);
+        assert_eq!(vector.get(2), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_vector_insert_panic() {
+        let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
+        vector.insert(1, TestValue(10));
+    }
+
+    #[test]
+    fn test_vector_swap_first_last() {
+        let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
+        assert_eq!(vector.first(), None);
+        assert_eq!(vector.last(), None);
+
+        vector.extend([TestValue(10), TestValue(20), TestValue(30)]);
+        vector.swap(0, 2);
+        vector.swap(1, 1);
+        assert_eq!(vector.first(), Some(&TestValue(30)));
+        assert_eq!(vector.last(), Some(&TestValue(10)));
+        assert_eq!(vector.get(1), Some(&TestValue(20)));
+    }
+
+    #[test]
+    fn test_vector_iter() {
+        let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
+        vector.extend((0..5).map(TestValue));
+
+        let mut iter = vector.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&TestValue(0)));
+        assert_eq!(iter.next_back(), Some(&TestValue(4)));
+        assert_eq!(iter.nth(1), Some(&TestValue(2)));
+        assert_eq!(iter.len(), 1);
+
+        for value in &mut vector {
+            value.0 *= 10;
+        }
+        vector
+            .iter_mut()
+            .rev()
+            .take(1)
+            .for_each(|value| value.0 += 1);
+        assert_eq!(
+            (&vector).into_iter().copied().collect::<Vec<_>>(),
+            [0, 10, 20, 30, 41].map(TestValue)
+        );
+    }
+
+    #[test]
+    fn test_vector_truncate_and_clear() {
+        let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
+        vector.extend((0..4).map(TestValue));
+        vector.flush();
+
+        vector.truncate(5);
+        assert_eq!(vector.len(), 4);
+        vector.truncate(2);
+        assert_eq!(vector.len(), 2);
+        assert_eq!(vector.get(2), None);
+        vector.flush();
+
+        let key = |index: u32| [&b"test"[..], &index.to_le_bytes()].concat();
+        assert!(storage_read(&key(1)).is_some());
+        assert!(storage_read(&key(2)).is_none());
+        assert!(storage_read(&key(3)).is_none());
+
+        vector.clear();
+        vector.flush();
+        assert!(vector.is_empty());
+        assert!(storage_read(&key(0)).is_none());
+    }
+
+    #[test]
+    fn test_vector_drain() {
+        let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
+        vector.extend((0..6).map(TestValue));
+
+        let drained = vector.drain(1..=2);
+        assert_eq!(drained.len(), 2);
+        assert_eq!(drained.collect::<Vec<_>>(), [1, 2].map(TestValue));
+        assert_eq!(
+            vector.iter().copied().collect::<Vec<_>>(),
+            [0, 3, 4, 5].map(TestValue)
+        );
+
+        // The elements are removed even if the iterator is not consumed
+        vector.drain(2..);
+        assert_eq!(
+            vector.iter().copied().collect::<Vec<_>>(),
+            [0, 3].map(TestValue)
+        );
+        vector.flush();
+        assert!(storage_read(&[&b"test"[..], &2u32.to_le_bytes()].concat()).is_none());
+
+        assert_eq!(
+            vector.drain(..).rev().collect::<Vec<_>>(),
+            [3, 0].map(TestValue)
+        );
+        assert!(vector.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_vector_drain_panic() {
+        let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
+        vector.push(TestValue(10));
+        vector.drain(0..2);
+    }
+
     #[test]
     fn test_push_persistence() {
         let mut vector: Vector<TestValue> = Vector::new(b"test".to_vec());
@@ -0,0 +1,91 @@
+use super::MockEnv;
+use crate::types::{Address, Balance, BlockHash, BlockNumber, TimeStamp};
+use std::rc::Rc;
+
+/// Builder of [`MockEnv`].
+///
+/// Values that are not set explicitly are taken from [`MockEnv::default`].
+#[derive(Default)]
+pub struct MockEnvBuilder {
+    env: MockEnv,
+}
+
+impl MockEnvBuilder {
+    /// Creates a builder of the default environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the address returned by [`crate::contract_owner_address`].
+    pub fn contract_owner_address(mut self, address: Address) -> Self {
+        self.env.contract_owner_address = address;
+        self
+    }
+
+    /// Sets the address returned by [`crate::caller_address`].
+    pub fn caller_address(mut self, address: Address) -> Self {
+        self.env.caller_address = address;
+        self
+    }
+
+    /// Sets the address returned by [`crate::contract_instance_address`].
+    pub fn contract_instance_address(mut self, address: Address) -> Self {
+        self.env.contract_instance_address = address;
+        self
+    }
+
+    /// Sets the block number returned by [`crate::block_number`].
+    pub fn block_number(mut self, block_number: BlockNumber) -> Self {
+        self.env.block_number = block_number;
+        self
+    }
+
+    /// Sets the block timestamp returned by [`crate::block_timestamp`].
+    pub fn block_timestamp(mut self, block_timestamp: TimeStamp) -> Self {
+        self.env.block_timestamp = block_timestamp;
+        self
+    }
+
+    /// Sets the block hash returned by [`crate::block_hash`].
+    pub fn block_hash(mut self, block_hash: BlockHash) -> Self {
+        self.env.block_hash = block_hash;
+        self
+    }
+
+    /// Sets the balance of `address`.
+    pub fn balance(mut self, address: Address, balance: Balance) -> Self {
+        self.env.balances.insert(address, balance);
+        self
+    }
+
+    /// Sets the input returned by [`crate::input`].
+    pub fn input(mut self, input: Vec<u8>) -> Self {
+        self.env.input = Some(input);
+        self
+    }
+
+    /// Writes key-value into the storage.
+    pub fn storage(mut self, key: Vec<u8>, value: Vec<u8>) -> Self {
+        self.env.storage.insert(key, value);
+        self
+    }
+
+    /// Registers the handler of `method_name` of the contract at `contract_address`. See
+    /// [`super::register_call_handler`].
+    pub fn call_handler(
+        mut self,
+        contract_address: Address,
+        method_name: &str,
+        handler: impl Fn(&[u8]) -> Option<Vec<u8>> + 'static,
+    ) -> Self {
+        self.env
+            .call_handlers
+            .insert(contract_address, method_name, Rc::new(handler));
+        self
+    }
+
+    /// Returns the environment. Pass it to [`super::set_env`] to use it.
+    pub fn build(self) -> MockEnv {
+        self.env
+    }
+}
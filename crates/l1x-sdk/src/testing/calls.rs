@@ -0,0 +1,260 @@
+//! Calls to other contracts made in the mocked environment.
+use super::journal::run_call;
+use super::with_env;
+use crate::contract_interaction::ContractCall;
+use crate::types::Address;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// Handler of a mocked contract method. Receives the serialized arguments and returns the
+/// serialized result, or `None` if the call fails.
+pub type MockCallHandler = Rc<dyn Fn(&[u8]) -> Option<Vec<u8>>>;
+
+#[derive(Clone, Default)]
+pub(crate) struct CallHandlers(HashMap<(Address, String), MockCallHandler>);
+
+impl CallHandlers {
+    pub(crate) fn insert(
+        &mut self,
+        contract_address: Address,
+        method_name: &str,
+        handler: MockCallHandler,
+    ) {
+        self.0
+            .insert((contract_address, method_name.to_string()), handler);
+    }
+}
+
+impl fmt::Debug for CallHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// Records the call and runs the handler registered for the called method.
+///
+/// While the handler runs, the caller address is the address of the calling contract, the contract
+/// instance address is the called one, and the read-only flag is taken from the call. The addresses
+/// and the flag are restored when the handler returns.
+///
+/// The handler shares the storage with the calling contract, see [`register_call_handler`].
+///
+/// Calls of methods without a handler fail.
+pub(crate) fn call_contract(call: &ContractCall) -> Option<Vec<u8>> {
+    let handler = with_env(|env| {
+        env.calls.push(call.clone());
+        env.call_handlers
+            .0
+            .get(&(call.contract_address, call.method_name.clone()))
+            .cloned()
+    });
+    let handler = handler?;
+    let (caller_address, contract_instance_address) = with_env(|env| {
+        let frame = (env.caller_address, env.contract_instance_address);
+        env.caller_address = env.contract_instance_address;
+        env.contract_instance_address = call.contract_address;
+        frame
+    });
+    // The environment is not borrowed here, so the handler can use the SDK functions. Failed
+    // calls are reverted.
+    let result = run_call(|| {
+        crate::set_read_only(call.read_only);
+        handler(&call.args).ok_or_else(|| "The call failed".to_string())
+    });
+    with_env(|env| {
+        env.caller_address = caller_address;
+        env.contract_instance_address = contract_instance_address;
+    });
+    result.ok()
+}
+
+/// Registers `handler` for `method_name` of the contract at `contract_address`.
+///
+/// Replaces the previously registered handler of the method.
+///
+/// The mocked environment has a single storage, so the storage functions called by the handler
+/// read and write the storage of the calling contract. On the L1X runtime each contract has its
+/// own storage, so keep the state of the mocked contract in the handler itself, e.g. in
+/// `Rc<RefCell<_>>`.
+pub fn register_call_handler(
+    contract_address: Address,
+    method_name: &str,
+    handler: impl Fn(&[u8]) -> Option<Vec<u8>> + 'static,
+) {
+    with_env(|env| {
+        env.call_handlers
+            .insert(contract_address, method_name, Rc::new(handler))
+    })
+}
+
+/// Removes the handler of `method_name`. Returns `true` if the handler was registered.
+pub fn remove_call_handler(contract_address: Address, method_name: &str) -> bool {
+    with_env(|env| {
+        env.call_handlers
+            .0
+            .remove(&(contract_address, method_name.to_string()))
+            .is_some()
+    })
+}
+
+/// Returns all calls made with [`crate::call_contract`] in the order they were made, including
+/// the failed ones.
+pub fn get_mock_calls() -> Vec<ContractCall> {
+    with_env(|env| env.calls.clone())
+}
+
+/// Clears the call log. Registered handlers are not affected.
+pub fn clear_mock_calls() {
+    with_env(|env| env.calls.clear())
+}
+
+/// Panics if `expected` call was not made. All fields are compared, including `read_only` and
+/// `fee_limit`.
+#[track_caller]
+pub fn assert_call_made(expected: &ContractCall) {
+    let calls = get_mock_calls();
+    assert!(
+        calls.contains(expected),
+        "Call {:?} not found in {:?}",
+        expected,
+        calls
+    );
+}
+
+/// Panics if `method_name` of the contract at `contract_address` was not called.
+#[track_caller]
+pub fn assert_called(contract_address: &Address, method_name: &str) {
+    let calls = get_mock_calls();
+    assert!(
+        calls
+            .iter()
+            .any(|call| call.contract_address == *contract_address
+                && call.method_name == method_name),
+        "Method {} of {} was not called",
+        method_name,
+        contract_address
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{reset_env, set_env, MockEnvBuilder};
+    use super::*;
+
+    fn call(contract_address: Address, method_name: &str, args: &[u8]) -> ContractCall {
+        ContractCall {
+            contract_address,
+            method_name: method_name.to_string(),
+            args: args.to_vec(),
+            read_only: true,
+            fee_limit: 0,
+        }
+    }
+
+    #[test]
+    fn test_call_handlers() {
+        let token = Address::from([5; 20]);
+        set_env(
+            MockEnvBuilder::new()
+                .call_handler(token, "echo", |args| Some(args.to_vec()))
+                .build(),
+        );
+        register_call_handler(token, "fail", |_| None);
+
+        assert_eq!(
+            crate::call_contract(&call(token, "echo", b"args")),
+            Some(b"args".to_vec())
+        );
+        assert_eq!(crate::call_contract(&call(token, "fail", b"")), None);
+        assert_eq!(crate::call_contract(&call(token, "unknown", b"")), None);
+
+        assert!(remove_call_handler(token, "echo"));
+        assert_eq!(crate::call_contract(&call(token, "echo", b"args")), None);
+        assert!(!remove_call_handler(token, "echo"));
+    }
+
+    #[test]
+    fn test_handler_shares_storage() {
+        let token = Address::from([5; 20]);
+        reset_env();
+        register_call_handler(token, "store", |args| {
+            crate::storage_write(b"key", args);
+            Some(Vec::new())
+        });
+        let mut store = call(token, "store", b"value");
+        store.read_only = false;
+
+        // The handler writes the storage of the calling contract
+        assert!(crate::call_contract(&store).is_some());
+        assert_eq!(crate::storage_read(b"key"), Some(b"value".to_vec()));
+    }
+
+    #[test]
+    fn test_handler_context() {
+        let token = Address::from([5; 20]);
+        let caller = Address::from([2; 20]);
+        let instance = Address::from([3; 20]);
+        set_env(
+            MockEnvBuilder::new()
+                .caller_address(caller)
+                .contract_instance_address(instance)
+                .build(),
+        );
+        register_call_handler(token, "whoami", move |_| {
+            assert_eq!(crate::caller_address(), instance);
+            assert_eq!(crate::contract_instance_address(), token);
+            Some(Vec::new())
+        });
+        register_call_handler(token, "store", |args| {
+            crate::storage_write(b"key", args);
+            Some(Vec::new())
+        });
+
+        let mut whoami = call(token, "whoami", b"");
+        whoami.read_only = false;
+        assert!(crate::call_contract(&whoami).is_some());
+
+        // The read-only call can't modify the storage
+        assert_eq!(crate::call_contract(&call(token, "store", b"value")), None);
+        assert_eq!(crate::storage_read(b"key"), None);
+
+        assert_eq!(crate::caller_address(), caller);
+        assert_eq!(crate::contract_instance_address(), instance);
+        assert!(!crate::is_read_only());
+    }
+
+    #[test]
+    fn test_call_log() {
+        let token = Address::from([5; 20]);
+        reset_env();
+        let mut transfer = call(token, "transfer", b"{}");
+        transfer.read_only = false;
+        transfer.fee_limit = 100;
+
+        crate::call_contract(&call(token, "balance_of", b"{}"));
+        crate::call_contract(&transfer);
+
+        assert_eq!(
+            get_mock_calls(),
+            vec![call(token, "balance_of", b"{}"), transfer.clone()]
+        );
+        assert_call_made(&transfer);
+        assert_called(&token, "balance_of");
+
+        let mut other_fee = transfer.clone();
+        other_fee.fee_limit = 1;
+        let result = std::panic::catch_unwind(|| assert_call_made(&other_fee));
+        assert!(result.is_err());
+
+        clear_mock_calls();
+        assert!(get_mock_calls().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "was not called")]
+    fn test_assert_called_panic() {
+        reset_env();
+        assert_called(&Address::from([5; 20]), "transfer");
+    }
+}
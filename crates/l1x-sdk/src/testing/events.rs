@@ -0,0 +1,81 @@
+//! Events emitted in the mocked environment.
+use super::with_env;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub(crate) fn emit_event(event_data: &[u8]) {
+    with_env(|env| env.events.push(event_data.to_vec()))
+}
+
+/// Returns the Borsh serialized events in the order they were emitted.
+pub fn get_mock_events() -> Vec<Vec<u8>> {
+    with_env(|env| env.events.clone())
+}
+
+/// Removes all captured events.
+pub fn clear_mock_events() {
+    with_env(|env| env.events.clear())
+}
+
+/// Removes all captured events and returns them deserialized as `T`.
+///
+/// # Panics
+///
+/// Panics if an event can't be deserialized as `T`
+#[track_caller]
+pub fn take_events<T: BorshDeserialize>() -> Vec<T> {
+    with_env(|env| std::mem::take(&mut env.events))
+        .iter()
+        .map(|event| T::try_from_slice(event).expect("Can't deserialize the event"))
+        .collect()
+}
+
+/// Panics if `expected` event was not emitted.
+///
+/// Events are compared in their Borsh serialized form, `T` doesn't need to implement `PartialEq`.
+#[track_caller]
+pub fn assert_event_emitted<T: BorshSerialize>(expected: &T) {
+    let expected = expected.try_to_vec().expect("Can't serialize the event");
+    assert!(
+        with_env(|env| env.events.contains(&expected)),
+        "The event was not emitted"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reset_env;
+    use super::*;
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    enum Event {
+        Minted { amount: u128 },
+        Burned { amount: u128 },
+    }
+
+    #[test]
+    fn test_events() {
+        reset_env();
+        crate::emit_event_experimental(Event::Minted { amount: 10 });
+        crate::emit_event_experimental(Event::Burned { amount: 5 });
+
+        assert_event_emitted(&Event::Burned { amount: 5 });
+        assert_eq!(get_mock_events().len(), 2);
+        assert_eq!(
+            take_events::<Event>(),
+            vec![Event::Minted { amount: 10 }, Event::Burned { amount: 5 }]
+        );
+        assert!(get_mock_events().is_empty());
+
+        crate::emit_event_experimental(Event::Minted { amount: 1 });
+        clear_mock_events();
+        assert!(take_events::<Event>().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The event was not emitted")]
+    fn test_assert_event_emitted_panic() {
+        reset_env();
+        crate::emit_event_experimental(Event::Minted { amount: 10 });
+        assert_event_emitted(&Event::Minted { amount: 11 });
+    }
+}
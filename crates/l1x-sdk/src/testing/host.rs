@@ -0,0 +1,93 @@
+//! In-memory implementations of the host functions used instead of [`l1x_sys`].
+use super::ledger::transfer;
+use super::with_env;
+use crate::contract_interaction::ContractCall;
+use crate::types::{Address, Balance, BlockHash, BlockNumber, TimeStamp};
+
+pub(crate) fn abort() -> ! {
+    std::panic!("Mocked panic function called!")
+}
+
+pub(crate) fn input() -> Option<Vec<u8>> {
+    with_env(|env| env.input.clone())
+}
+
+pub(crate) fn output(data: &[u8]) {
+    with_env(|env| env.output = data.to_vec())
+}
+
+pub(crate) fn msg(message: &str) {
+    with_env(|env| env.messages.push(message.to_owned()))
+}
+
+pub(crate) fn register_len(register_id: u64) -> Option<u64> {
+    with_env(|env| {
+        env.registers
+            .get(&register_id)
+            .map(|data| data.len() as u64)
+    })
+}
+
+pub(crate) fn read_register(register_id: u64) -> Option<Vec<u8>> {
+    with_env(|env| env.registers.get(&register_id).cloned())
+}
+
+pub(crate) fn write_register(register_id: u64, data: &[u8]) {
+    with_env(|env| env.registers.insert(register_id, data.to_vec()));
+}
+
+pub(crate) fn storage_write(key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+    with_env(|env| env.storage_write(key, value))
+}
+
+pub(crate) fn storage_read(key: &[u8]) -> Option<Vec<u8>> {
+    with_env(|env| env.storage.get(key).cloned())
+}
+
+pub(crate) fn storage_remove(key: &[u8]) -> Option<Vec<u8>> {
+    with_env(|env| env.storage_remove(key))
+}
+
+pub(crate) fn contract_owner_address() -> Address {
+    with_env(|env| env.contract_owner_address)
+}
+
+pub(crate) fn caller_address() -> Address {
+    with_env(|env| env.caller_address)
+}
+
+pub(crate) fn contract_instance_address() -> Address {
+    with_env(|env| env.contract_instance_address)
+}
+
+pub(crate) fn address_balance(address: &Address) -> Balance {
+    with_env(|env| env.balances.get(address).copied().unwrap_or_default())
+}
+
+pub(crate) fn transfer_to(to: &Address, amount: Balance) -> bool {
+    transfer(contract_instance_address(), *to, amount)
+}
+
+pub(crate) fn transfer_from_caller(amount: Balance) -> bool {
+    transfer(caller_address(), contract_instance_address(), amount)
+}
+
+pub(crate) fn block_hash() -> BlockHash {
+    with_env(|env| env.block_hash)
+}
+
+pub(crate) fn block_number() -> BlockNumber {
+    with_env(|env| env.block_number)
+}
+
+pub(crate) fn block_timestamp() -> TimeStamp {
+    with_env(|env| env.block_timestamp)
+}
+
+pub(crate) fn call_contract(call: &ContractCall) -> Option<Vec<u8>> {
+    super::calls::call_contract(call)
+}
+
+pub(crate) fn emit_event(event_data: &[u8]) {
+    super::events::emit_event(event_data)
+}
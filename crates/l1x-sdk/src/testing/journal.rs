@@ -0,0 +1,246 @@
+//! Reverting the changes of failed calls in the mocked environment.
+use super::{with_env, MockEnv};
+use crate::types::{Address, Balance};
+use std::panic::{self, AssertUnwindSafe};
+
+/// A change that can be reverted.
+#[derive(Clone, Debug)]
+enum JournalEntry {
+    Storage {
+        key: Vec<u8>,
+        previous: Option<Vec<u8>>,
+    },
+    Balance {
+        address: Address,
+        previous: Option<Balance>,
+    },
+}
+
+/// Storage and balance changes made by the running calls.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Journal {
+    /// The number of running calls. Changes are only journaled while a call is running.
+    depth: usize,
+    entries: Vec<JournalEntry>,
+}
+
+/// The state of [`MockEnv`] at the start of a call.
+struct Checkpoint {
+    entries_len: usize,
+    transfers_len: usize,
+    events_len: usize,
+}
+
+impl MockEnv {
+    /// Writes key-value into the storage. Returns the replaced value.
+    pub(crate) fn storage_write(&mut self, key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+        let previous = self.storage.insert(key.to_vec(), value.to_vec());
+        self.record(JournalEntry::Storage {
+            key: key.to_vec(),
+            previous: previous.clone(),
+        });
+        previous
+    }
+
+    /// Removes the key from the storage. Returns the removed value.
+    pub(crate) fn storage_remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        let previous = self.storage.remove(key)?;
+        self.record(JournalEntry::Storage {
+            key: key.to_vec(),
+            previous: Some(previous.clone()),
+        });
+        Some(previous)
+    }
+
+    pub(crate) fn set_balance(&mut self, address: Address, balance: Balance) {
+        let previous = self.balances.insert(address, balance);
+        self.record(JournalEntry::Balance { address, previous });
+    }
+
+    fn record(&mut self, entry: JournalEntry) {
+        if self.journal.depth > 0 {
+            self.journal.entries.push(entry);
+        }
+    }
+
+    fn begin(&mut self) -> Checkpoint {
+        self.journal.depth += 1;
+        Checkpoint {
+            entries_len: self.journal.entries.len(),
+            transfers_len: self.transfers.len(),
+            events_len: self.events.len(),
+        }
+    }
+
+    fn end(&mut self) {
+        self.journal.depth -= 1;
+        if self.journal.depth == 0 {
+            self.journal.entries.clear();
+        }
+    }
+
+    /// Reverts the storage and balance changes, the transfers and the events made after
+    /// `checkpoint`.
+    fn revert(&mut self, checkpoint: Checkpoint) {
+        while self.journal.entries.len() > checkpoint.entries_len {
+            match self
+                .journal
+                .entries
+                .pop()
+                .expect("The journal is not empty")
+            {
+                JournalEntry::Storage { key, previous } => {
+                    match previous {
+                        Some(previous) => self.storage.insert(key, previous),
+                        None => self.storage.remove(&key),
+                    };
+                }
+                JournalEntry::Balance { address, previous } => {
+                    match previous {
+                        Some(previous) => self.balances.insert(address, previous),
+                        None => self.balances.remove(&address),
+                    };
+                }
+            }
+        }
+        self.transfers.truncate(checkpoint.transfers_len);
+        self.events.truncate(checkpoint.events_len);
+    }
+}
+
+/// Runs `f` as a call. If `f` panics or returns `Err`, the changes it made are reverted.
+pub(crate) fn run_call<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    let checkpoint = with_env(MockEnv::begin);
+    let read_only = crate::is_read_only();
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string()))
+    });
+    crate::set_read_only(read_only);
+    with_env(|env| {
+        if result.is_err() {
+            env.revert(checkpoint);
+        }
+        env.end();
+    });
+    result
+}
+
+/// Runs `f` the way the blockchain runs a contract call: if `f` panics, the storage and balance
+/// changes, the transfers and the events it made are reverted and the panic message is returned.
+/// The message of [`crate::panic`] is returned as is.
+///
+/// Messages and the call log are kept. Calls made by `f` with [`crate::call_contract`] are
+/// reverted the same way when their handlers fail.
+///
+/// # Example
+/// ```
+/// use l1x_sdk::testing;
+///
+/// testing::reset_env();
+/// let result = testing::execute(|| {
+///     l1x_sdk::storage_write(b"key", b"value");
+///     l1x_sdk::panic("failed");
+/// });
+///
+/// assert_eq!(result, Err("failed".to_string()));
+/// assert_eq!(l1x_sdk::storage_read(b"key"), None);
+/// ```
+pub fn execute<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    run_call(|| Ok(f()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        get_mock_transfers, register_call_handler, reset_env, set_env, take_events, MockEnvBuilder,
+    };
+    use super::*;
+    use crate::contract_interaction::ContractCall;
+
+    #[test]
+    fn test_execute_commit() {
+        reset_env();
+        let result = execute(|| {
+            crate::storage_write(b"key", b"value");
+            42
+        });
+
+        assert_eq!(result, Ok(42));
+        assert_eq!(crate::storage_read(b"key"), Some(b"value".to_vec()));
+        // Changes are journaled only while the call is running
+        assert!(with_env(|env| env.journal.entries.is_empty()));
+    }
+
+    #[test]
+    fn test_execute_revert() {
+        let caller = Address::from([2; 20]);
+        set_env(
+            MockEnvBuilder::new()
+                .caller_address(caller)
+                .balance(caller, 100)
+                .storage(b"key".to_vec(), b"value".to_vec())
+                .build(),
+        );
+
+        let result = execute(|| {
+            crate::storage_write(b"key", b"new");
+            crate::storage_write(b"other", b"value");
+            crate::transfer_from_caller(40);
+            crate::emit_event_experimental(1u8);
+            crate::set_read_only(true);
+            panic!("failed")
+        });
+
+        assert_eq!(result, Err("failed".to_string()));
+        assert_eq!(crate::storage_read(b"key"), Some(b"value".to_vec()));
+        assert_eq!(crate::storage_read(b"other"), None);
+        assert_eq!(crate::address_balance(&caller), 100);
+        assert_eq!(crate::contract_instance_balance(), 0);
+        assert!(get_mock_transfers().is_empty());
+        assert!(take_events::<u8>().is_empty());
+        assert!(!crate::is_read_only());
+    }
+
+    #[test]
+    fn test_revert_nested_calls() {
+        let token = Address::from([5; 20]);
+        reset_env();
+        register_call_handler(token, "fail", |_| {
+            crate::storage_write(b"nested", b"value");
+            None
+        });
+        register_call_handler(token, "write", |_| {
+            crate::storage_remove(b"key");
+            Some(Vec::new())
+        });
+        let call = |method_name: &str| ContractCall {
+            contract_address: token,
+            method_name: method_name.to_string(),
+            args: Vec::new(),
+            read_only: false,
+            fee_limit: 0,
+        };
+        crate::storage_write(b"key", b"value");
+
+        // The failed nested call is reverted, the caller continues
+        let result = execute(|| {
+            crate::storage_write(b"caller", b"value");
+            crate::call_contract(&call("fail"))
+        });
+        assert_eq!(result, Ok(None));
+        assert_eq!(crate::storage_read(b"nested"), None);
+        assert_eq!(crate::storage_read(b"caller"), Some(b"value".to_vec()));
+
+        // The successful nested call is reverted with the failed caller
+        let result = execute(|| {
+            crate::call_contract(&call("write")).unwrap();
+            crate::panic("failed")
+        });
+        assert_eq!(result, Err("failed".to_string()));
+        assert_eq!(crate::storage_read(b"key"), Some(b"value".to_vec()));
+    }
+}
@@ -0,0 +1,165 @@
+//! Balances and token transfers of the mocked environment.
+use super::with_env;
+use crate::types::{Address, Balance};
+
+/// A successful transfer of L1X tokens recorded by the mocked environment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub amount: Balance,
+}
+
+/// Moves `amount` from `from` to `to` and records the transfer.
+///
+/// Returns `false` and leaves the ledger unchanged if `from` doesn't have enough funds.
+pub(crate) fn transfer(from: Address, to: Address, amount: Balance) -> bool {
+    with_env(|env| {
+        let from_balance = env.balances.get(&from).copied().unwrap_or_default();
+        let Some(from_balance) = from_balance.checked_sub(amount) else {
+            return false;
+        };
+        env.set_balance(from, from_balance);
+        let to_balance = env.balances.get(&to).copied().unwrap_or_default();
+        env.set_balance(to, to_balance.saturating_add(amount));
+        env.transfers.push(MockTransfer { from, to, amount });
+        true
+    })
+}
+
+/// Returns the successful transfers in the order they were made.
+pub fn get_mock_transfers() -> Vec<MockTransfer> {
+    with_env(|env| env.transfers.clone())
+}
+
+/// Clears the transfer log. Balances are not affected.
+pub fn clear_mock_transfers() {
+    with_env(|env| env.transfers.clear())
+}
+
+/// Returns all non-zero balances sorted by address.
+pub fn get_mock_balances() -> Vec<(Address, Balance)> {
+    let mut balances: Vec<_> = with_env(|env| {
+        env.balances
+            .iter()
+            .filter(|(_, balance)| **balance != 0)
+            .map(|(address, balance)| (*address, *balance))
+            .collect()
+    });
+    balances.sort();
+    balances
+}
+
+/// Panics if the balance of `address` is not `expected`.
+#[track_caller]
+pub fn assert_balance(address: &Address, expected: Balance) {
+    let balance = crate::address_balance(address);
+    assert_eq!(
+        balance, expected,
+        "Unexpected balance of {}: {} instead of {}",
+        address, balance, expected
+    );
+}
+
+/// Panics if no transfer of `amount` from `from` to `to` was made.
+#[track_caller]
+pub fn assert_transferred(from: &Address, to: &Address, amount: Balance) {
+    let expected = MockTransfer {
+        from: *from,
+        to: *to,
+        amount,
+    };
+    let transfers = get_mock_transfers();
+    assert!(
+        transfers.contains(&expected),
+        "Transfer {:?} not found in {:?}",
+        expected,
+        transfers
+    );
+}
+
+/// Panics if the sum of all balances is not `expected`.
+///
+/// Transfers never create or burn tokens, so the total supply set by the test must be preserved.
+#[track_caller]
+pub fn assert_total_supply(expected: Balance) {
+    let total = with_env(|env| {
+        env.balances
+            .values()
+            .fold(0, |total: Balance, balance| total.saturating_add(*balance))
+    });
+    assert_eq!(total, expected, "Unexpected total supply");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{reset_env, set_env, set_mock_balance, MockEnvBuilder};
+    use super::*;
+
+    #[test]
+    fn test_transfer_log() {
+        let caller = Address::from([2; 20]);
+        let receiver = Address::from([4; 20]);
+        set_env(
+            MockEnvBuilder::new()
+                .caller_address(caller)
+                .balance(caller, 100)
+                .build(),
+        );
+        let instance = crate::contract_instance_address();
+
+        crate::transfer_from_caller(60);
+        crate::transfer_to(&receiver, 50);
+
+        assert_eq!(
+            get_mock_transfers(),
+            vec![
+                MockTransfer {
+                    from: caller,
+                    to: instance,
+                    amount: 60
+                },
+                MockTransfer {
+                    from: instance,
+                    to: receiver,
+                    amount: 50
+                },
+            ]
+        );
+        assert_transferred(&caller, &instance, 60);
+        assert_balance(&caller, 40);
+        assert_balance(&instance, 10);
+        assert_balance(&receiver, 50);
+        assert_eq!(
+            get_mock_balances(),
+            vec![(caller, 40), (receiver, 50), (instance, 10)]
+        );
+        assert_total_supply(100);
+
+        clear_mock_transfers();
+        assert!(get_mock_transfers().is_empty());
+        assert_balance(&receiver, 50);
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        reset_env();
+        let caller = crate::caller_address();
+        set_mock_balance(caller, 10);
+
+        assert!(!transfer(caller, crate::contract_instance_address(), 11));
+        assert_balance(&caller, 10);
+        assert!(get_mock_transfers().is_empty());
+
+        let result = std::panic::catch_unwind(|| crate::transfer_from_caller(11));
+        assert!(result.is_err());
+        assert_balance(&caller, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer")]
+    fn test_assert_transferred_panic() {
+        reset_env();
+        assert_transferred(&Address::from([1; 20]), &Address::from([2; 20]), 1);
+    }
+}
@@ -0,0 +1,276 @@
+//! A mocked blockchain environment for unit-testing contracts.
+//!
+//! When the `mock` feature is enabled, every host function of the SDK is served by an in-memory
+//! [`MockEnv`] instead of the L1X runtime, so contracts can be tested on the host with
+//! `cargo test`. The environment is thread-local, tests running in parallel don't interfere.
+//!
+//! The feature must be disabled when the contract is built for deployment.
+//!
+//! # Example
+//! ```
+//! use l1x_sdk::testing::{self, MockEnvBuilder};
+//! use l1x_sdk::types::Address;
+//!
+//! let alice = Address::from([1; 20]);
+//! testing::set_env(MockEnvBuilder::new().caller_address(alice).balance(alice, 100).build());
+//!
+//! assert_eq!(l1x_sdk::caller_address(), alice);
+//! assert_eq!(l1x_sdk::address_balance(&alice), 100);
+//!
+//! testing::advance_time(3600);
+//! assert_eq!(l1x_sdk::block_timestamp(), 3600);
+//! ```
+mod builder;
+mod calls;
+mod events;
+pub(crate) mod host;
+mod journal;
+mod ledger;
+
+pub use builder::MockEnvBuilder;
+pub use calls::{
+    assert_call_made, assert_called, clear_mock_calls, get_mock_calls, register_call_handler,
+    remove_call_handler, MockCallHandler,
+};
+pub use events::{assert_event_emitted, clear_mock_events, get_mock_events, take_events};
+pub use journal::execute;
+pub use ledger::{
+    assert_balance, assert_total_supply, assert_transferred, clear_mock_transfers,
+    get_mock_balances, get_mock_transfers, MockTransfer,
+};
+
+use crate::contract_interaction::ContractCall;
+use crate::types::{Address, Balance, BlockHash, BlockNumber, TimeStamp};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+const CONTRACT_OWNER_ADDRESS: &[u8; 20] = b"mock_owner_address11";
+const CONTRACT_INSTANCE_ADDRESS: &[u8; 20] = b"mock_instance_addres";
+const CALLER_ADDRESS: &[u8; 20] = b"mock_caller_address1";
+
+thread_local! {
+    static MOCK_ENV: RefCell<MockEnv> = RefCell::new(MockEnv::default());
+}
+
+/// The state of the mocked blockchain. Use [`MockEnvBuilder`] to create it.
+#[derive(Clone, Debug)]
+pub struct MockEnv {
+    pub(crate) storage: HashMap<Vec<u8>, Vec<u8>>,
+    pub(crate) registers: HashMap<u64, Vec<u8>>,
+    pub(crate) input: Option<Vec<u8>>,
+    pub(crate) output: Vec<u8>,
+    pub(crate) messages: Vec<String>,
+    pub(crate) contract_owner_address: Address,
+    pub(crate) caller_address: Address,
+    pub(crate) contract_instance_address: Address,
+    pub(crate) block_number: BlockNumber,
+    pub(crate) block_timestamp: TimeStamp,
+    pub(crate) block_hash: BlockHash,
+    pub(crate) balances: HashMap<Address, Balance>,
+    pub(crate) transfers: Vec<MockTransfer>,
+    pub(crate) call_handlers: calls::CallHandlers,
+    pub(crate) calls: Vec<ContractCall>,
+    pub(crate) events: Vec<Vec<u8>>,
+    pub(crate) journal: journal::Journal,
+}
+
+impl Default for MockEnv {
+    fn default() -> Self {
+        Self {
+            storage: HashMap::new(),
+            registers: HashMap::new(),
+            input: Some(Vec::new()),
+            output: Vec::new(),
+            messages: Vec::new(),
+            contract_owner_address: Address::from(CONTRACT_OWNER_ADDRESS),
+            caller_address: Address::from(CALLER_ADDRESS),
+            contract_instance_address: Address::from(CONTRACT_INSTANCE_ADDRESS),
+            block_number: 0,
+            block_timestamp: 0,
+            block_hash: BlockHash::default(),
+            balances: HashMap::new(),
+            transfers: Vec::new(),
+            call_handlers: calls::CallHandlers::default(),
+            calls: Vec::new(),
+            events: Vec::new(),
+            journal: journal::Journal::default(),
+        }
+    }
+}
+
+pub(crate) fn with_env<R>(f: impl FnOnce(&mut MockEnv) -> R) -> R {
+    MOCK_ENV.with(|env| f(&mut env.borrow_mut()))
+}
+
+/// Replaces the mocked environment of the current thread with `env`.
+///
+/// Also resets the read-only flag set by [`crate::set_read_only`].
+pub fn set_env(env: MockEnv) {
+    crate::set_read_only(false);
+    with_env(|current| *current = env);
+}
+
+/// Replaces the mocked environment of the current thread with the default one.
+pub fn reset_env() {
+    set_env(MockEnv::default())
+}
+
+/// Sets the input returned by [`crate::input`].
+pub fn set_mock_input(data: Vec<u8>) {
+    with_env(|env| env.input = Some(data));
+}
+
+/// Returns the data written with [`crate::output`].
+pub fn get_mock_output() -> Vec<u8> {
+    with_env(|env| env.output.clone())
+}
+
+/// Returns the messages written with [`crate::msg`].
+pub fn get_mock_msgs() -> Vec<String> {
+    with_env(|env| env.messages.clone())
+}
+
+/// Clears the input, the output and the messages.
+pub fn clear_mock_io() {
+    with_env(|env| {
+        env.input = None;
+        env.output = Vec::new();
+        env.messages = Vec::new();
+    })
+}
+
+/// Removes the key from the mocked storage. Returns `true` if the key existed.
+pub fn remove_from_mock_storage(key: &[u8]) -> bool {
+    with_env(|env| env.storage.remove(key).is_some())
+}
+
+/// Sets the address returned by [`crate::contract_owner_address`].
+pub fn set_mock_contract_owner_address(owner_address: Address) {
+    with_env(|env| env.contract_owner_address = owner_address)
+}
+
+/// Sets the address returned by [`crate::caller_address`].
+pub fn set_mock_caller_address(caller_address: Address) {
+    with_env(|env| env.caller_address = caller_address)
+}
+
+/// Sets the address returned by [`crate::contract_instance_address`].
+pub fn set_mock_contract_instance_address(contract_instance_address: Address) {
+    with_env(|env| env.contract_instance_address = contract_instance_address)
+}
+
+/// Sets the balance returned by [`crate::address_balance`] for `address`.
+pub fn set_mock_balance(address: Address, balance: Balance) {
+    with_env(|env| {
+        env.balances.insert(address, balance);
+    })
+}
+
+/// Sets the block number returned by [`crate::block_number`].
+pub fn set_mock_block_number(block_number: BlockNumber) {
+    with_env(|env| env.block_number = block_number)
+}
+
+/// Sets the block timestamp returned by [`crate::block_timestamp`].
+pub fn set_mock_block_timestamp(block_timestamp: TimeStamp) {
+    with_env(|env| env.block_timestamp = block_timestamp)
+}
+
+/// Sets the block hash returned by [`crate::block_hash`].
+pub fn set_mock_block_hash(block_hash: BlockHash) {
+    with_env(|env| env.block_hash = block_hash)
+}
+
+/// Increases the block number returned by [`crate::block_number`] by `blocks`.
+pub fn advance_blocks(blocks: BlockNumber) {
+    with_env(|env| env.block_number += blocks)
+}
+
+/// Increases the block timestamp returned by [`crate::block_timestamp`] by `secs`.
+pub fn advance_time(secs: TimeStamp) {
+    with_env(|env| env.block_timestamp += secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_env_builder() {
+        let owner = Address::from([1; 20]);
+        let caller = Address::from([2; 20]);
+        let instance = Address::from([3; 20]);
+        set_env(
+            MockEnvBuilder::new()
+                .contract_owner_address(owner)
+                .caller_address(caller)
+                .contract_instance_address(instance)
+                .block_number(7)
+                .block_timestamp(1_700_000_000)
+                .block_hash([9; 32])
+                .balance(caller, 100)
+                .input(vec![1, 2, 3])
+                .storage(b"key".to_vec(), b"value".to_vec())
+                .build(),
+        );
+
+        assert_eq!(crate::contract_owner_address(), owner);
+        assert_eq!(crate::caller_address(), caller);
+        assert_eq!(crate::contract_instance_address(), instance);
+        assert_eq!(crate::block_number(), 7);
+        assert_eq!(crate::block_timestamp(), 1_700_000_000);
+        assert_eq!(crate::block_hash(), [9; 32]);
+        assert_eq!(crate::address_balance(&caller), 100);
+        assert_eq!(crate::input(), Some(vec![1, 2, 3]));
+        assert_eq!(crate::storage_read(b"key"), Some(b"value".to_vec()));
+    }
+
+    #[test]
+    fn test_advance_block() {
+        set_env(
+            MockEnvBuilder::new()
+                .block_number(10)
+                .block_timestamp(1_000)
+                .build(),
+        );
+
+        advance_blocks(5);
+        advance_time(60);
+        assert_eq!(crate::block_number(), 15);
+        assert_eq!(crate::block_timestamp(), 1_060);
+
+        set_mock_block_number(1);
+        set_mock_block_timestamp(2);
+        set_mock_block_hash([3; 32]);
+        assert_eq!(crate::block_number(), 1);
+        assert_eq!(crate::block_timestamp(), 2);
+        assert_eq!(crate::block_hash(), [3; 32]);
+    }
+
+    #[test]
+    fn test_mock_transfers() {
+        let caller = Address::from([2; 20]);
+        let receiver = Address::from([4; 20]);
+        set_env(
+            MockEnvBuilder::new()
+                .caller_address(caller)
+                .balance(caller, 100)
+                .build(),
+        );
+
+        crate::transfer_from_caller(60);
+        assert_eq!(crate::address_balance(&caller), 40);
+        assert_eq!(crate::contract_instance_balance(), 60);
+
+        crate::transfer_to(&receiver, 50);
+        assert_eq!(crate::contract_instance_balance(), 10);
+        assert_eq!(crate::address_balance(&receiver), 50);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mock_transfer_insufficient_funds() {
+        reset_env();
+        crate::transfer_to(&Address::from([4; 20]), 1);
+    }
+}
@@ -56,6 +56,17 @@ macro_rules! impl_str_type {
                 })?))
             }
         }
+
+        #[cfg(feature = "abi")]
+        impl schemars::JsonSchema for $iden {
+            fn schema_name() -> String {
+                stringify!($iden).to_string()
+            }
+
+            fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                String::json_schema(gen)
+            }
+        }
     };
 }
 
@@ -92,6 +103,17 @@ impl<'de> Deserialize<'de> for U256 {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for U256 {
+    fn schema_name() -> String {
+        "U256".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +193,17 @@ impl<'de> Deserialize<'de> for Address {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for Address {
+    fn schema_name() -> String {
+        "Address".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::types::Address;